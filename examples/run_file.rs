@@ -0,0 +1,23 @@
+use std::{env, path::Path, process::ExitCode};
+
+use esoteric_vm::Machine;
+
+fn main() -> ExitCode {
+    // the program file is the first argument
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: run_file <program.evm>");
+        return ExitCode::FAILURE;
+    };
+
+    // initialize a new machine
+    let mut machine = Machine::default();
+
+    // load the program at address 0
+    if let Err(error) = machine.load_file(Path::new(&path), 0) {
+        eprintln!("can't load {path}: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    // run machine until it halts and exit with register A
+    ExitCode::from(machine.run())
+}
@@ -0,0 +1,788 @@
+//! A runtime assembler for esoteric assembly text.
+//!
+//! More info at [`assemble`].
+
+use std::{borrow::Cow, collections::HashMap, error::Error, fmt, sync::OnceLock};
+
+use crate::{
+    instruction::{
+        normalize_mnemonic as normalize, DataOrInstruction, Instruction, InstructionKind, Operand,
+    },
+    machine::decoder::{choice_to_byte, Decoder},
+};
+
+/// An error from [`assemble`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    /// The line (starting at 1) of the statement that failed to assemble
+    pub line: usize,
+    /// What went wrong
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AssembleError {}
+
+/// A listing of an assembled program.
+///
+/// Displaying it shows the address and the bytes
+/// of every statement next to its source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Listing {
+    /// The assembled statements, in order
+    pub lines: Vec<ListingLine>,
+}
+
+/// One assembled statement of a [`Listing`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingLine {
+    /// The line (starting at 1) the statement starts on
+    pub line: usize,
+    /// The address of the first byte, if the program is loaded at address 0
+    pub address: u16,
+    /// The bytes the statement assembles to
+    pub bytes: Vec<u8>,
+    /// The source of the statement, without the trailing `;`
+    pub source: String,
+}
+
+impl fmt::Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            write!(f, "{:5}  {:#06x} ", line.line, line.address)?;
+            for byte in line.bytes.iter().take(8) {
+                write!(f, " {byte:02x}")?;
+            }
+            let padding = 8_usize.saturating_sub(line.bytes.len()).saturating_mul(3);
+            let ellipsis = if line.bytes.len() > 8 { "..." } else { "   " };
+            writeln!(f, "{:padding$}{ellipsis}  {}", "", line.source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assembles esoteric assembly text at runtime.
+///
+/// This accepts the same statements as [`esoteric_assembly`](crate::esoteric_assembly),
+/// so programs can be copied from the macro's examples.
+///
+/// # Grammar
+///
+/// ```text
+/// program   = { statement ";" } [ statement ]
+/// statement = [ offset ":" ] [ label ":" ] mnemonic [ operand { "," operand } ]
+/// offset    = integer
+/// label     = identifier
+/// operand   = integer | float | byte | "true" | "false" | choice | array | bytestring
+///           | label [ "as" type ]
+/// integer   = [ "-" ] ( digits | "0x" hexdigits | "0o" octdigits | "0b" bindigits )
+/// float     = [ "-" ] digits [ "." digits ] [ "e" [ "-" ] digits ]
+/// byte      = "b'" character "'"
+/// choice    = "None" | "Some(" choice ")" | "Some(())"
+/// array     = "[" operand ";" integer "]" | "[" operand { "," operand } "]"
+/// ```
+///
+/// - Mnemonics are matched case-insensitively.
+/// - The offset is optional and unchecked.
+/// - A label is the address of its statement (counted from 0), like in the macro.
+///   Labels can be used before they're defined and cast with `as` (e.g. `pushi end as u8`),
+///   which truncates them like in Rust. They can't be the amount of `fill` or the alignment
+///   of `align`, since those change the addresses of the labels after them.
+/// - The `;` after the last statement is optional.
+/// - Whitespace (including newlines) is allowed between any two tokens,
+///   and empty statements are skipped.
+/// - `//` line comments and `/* */` block comments are skipped, except in literals.
+/// - Digits can be separated with `_`.
+/// - Byte (string) literals support the escapes
+///   `\n`, `\r`, `\t`, `\0`, `\\`, `\'`, `\"` and `\xNN`.
+///
+/// `Ωchoiceset` takes a choice and `ldiř` takes an array of 37 values.
+/// The `data` statement takes a byte string literal like `b"Hello, world!\n\0"`
+/// or an array of bytes like `[0x00, b'.', 65]`
+/// and the `byte` statement takes a single byte.
+/// Data is returned as [`DataOrInstruction::ByteData`], one per byte.
+///
+/// The `fill` statement takes a byte and an amount and is returned as
+/// a [`DataOrInstruction::Fill`] of that many copies of the byte.
+///
+/// The `align` statement pads with zero bytes (returned as [`DataOrInstruction::Zeroed`])
+/// up to the next address that's a multiple of its value, and pads nothing if the address
+/// already is one. Addresses start at 0, so load the program at an address
+/// that's a multiple of the alignment to keep it.
+///
+/// The `pushstr` statement takes a byte string literal and is returned as
+/// a [`Pushi`](crate::instruction::Instruction::Pushi) instruction per byte.
+///
+/// # Errors
+///
+/// Returns an [`AssembleError`] for the first statement that can't be assembled,
+/// which includes using a label that isn't defined, defining one twice
+/// or using one as the amount of `fill` or the alignment of `align`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{assembly::assemble, Machine};
+/// let program = assemble(
+///     "0: pushi b'.'; 2: pop 28657; 5: ldidp 28657; // set the dot pointer
+///      8: writeline 13;
+///
+///      /* stop the program */
+///      11: Ωtheendisnear; 12: Ωskiptothechase;
+///      13: data b\"Hello, world!\\n\\0\";",
+/// )
+/// .unwrap();
+///
+/// let mut machine = Machine::default();
+/// machine.load(&program, 0);
+/// machine.run();
+/// ```
+pub fn assemble(src: &str) -> Result<Vec<DataOrInstruction<'static>>, AssembleError> {
+    assemble_with_listing(src).map(|(program, _)| program)
+}
+
+/// Assembles esoteric assembly text at runtime like [`assemble`]
+/// and also returns a [`Listing`] of where every statement lands.
+///
+/// # Errors
+///
+/// Returns an [`AssembleError`] for the first statement that can't be assembled.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::assembly::assemble_with_listing;
+/// let (_, listing) = assemble_with_listing("pushi 46;\npop 28657;").unwrap();
+///
+/// assert_eq!(listing.lines[1].line, 2);
+/// assert_eq!(listing.lines[1].address, 2);
+/// assert_eq!(listing.lines[1].source, "pop 28657");
+/// ```
+pub fn assemble_with_listing(
+    src: &str,
+) -> Result<(Vec<DataOrInstruction<'static>>, Listing), AssembleError> {
+    let src = strip_comments(src);
+    let statements = statements(&src);
+
+    // first pass: every label is 0, which doesn't change the size of anything
+    // (labels can't be used in the operands that change the size)
+    let mut zeroes = HashMap::new();
+    for &(line, source) in &statements {
+        if let (Some(label), _) = split_label(strip_offset(source)) {
+            if zeroes.insert(label, 0).is_some() {
+                return Err(AssembleError {
+                    line,
+                    message: format!("the label `{label}` is defined twice"),
+                });
+            }
+        }
+    }
+    let (_, _, labels) = assemble_statements(&statements, &zeroes)?;
+
+    // second pass: every label is the address of its statement
+    let (program, listing, _) = assemble_statements(&statements, &labels)?;
+    Ok((program, listing))
+}
+
+/// The addresses of the labels of a program.
+type Labels<'a> = HashMap<&'a str, u16>;
+
+/// Assembles statements with the addresses of the labels in `labels`,
+/// also returning the addresses the labels actually ended up at.
+fn assemble_statements<'a>(
+    statements: &[(usize, &'a str)],
+    labels: &Labels,
+) -> Result<(Vec<DataOrInstruction<'static>>, Listing, Labels<'a>), AssembleError> {
+    let mut program = Vec::new();
+    let mut listing = Listing::default();
+    let mut addresses = HashMap::new();
+    let mut address: usize = 0;
+
+    for &(line, source) in statements {
+        let error = |message: String| AssembleError { line, message };
+
+        let (label, statement_source) = split_label(strip_offset(source));
+        #[allow(clippy::cast_possible_truncation)]
+        if let Some(label) = label {
+            addresses.insert(label, address as u16);
+        }
+
+        let (data, bytes) = statement(statement_source, address, labels).map_err(error)?;
+
+        let start = address;
+        address = address.saturating_add(bytes.len());
+        if address > 0xFFFF {
+            return Err(error("the program doesn't fit in memory".to_owned()));
+        }
+
+        program.extend(data);
+        listing.lines.push(ListingLine {
+            line,
+            #[allow(clippy::cast_possible_truncation)]
+            address: start as u16,
+            bytes,
+            source: source.to_owned(),
+        });
+    }
+
+    Ok((program, listing, addresses))
+}
+
+/// Replaces comments with whitespace, keeping newlines so that line numbers stay the same.
+fn strip_comments(src: &str) -> String {
+    let mut stripped = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    let mut quote = None;
+    let mut escaped = false;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            stripped.push(c);
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                stripped.push(' ');
+            }
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                stripped.push(c);
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+/// Splits the source into statements (separated by `;` outside of brackets)
+/// with the line each of them starts on.
+fn statements(src: &str) -> Vec<(usize, &str)> {
+    let mut statements = Vec::new();
+
+    let mut line: usize = 1;
+    let mut start = 0;
+    let mut start_line = None;
+    let mut depth: usize = 0;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in src.char_indices() {
+        if c == '\n' {
+            line = line.saturating_add(1);
+        }
+
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            ';' if depth == 0 => {
+                if let (Some(l), Some(s)) = (start_line.take(), src.get(start..i)) {
+                    statements.push((l, s.trim()));
+                }
+                start = i.saturating_add(1);
+            }
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' => depth = depth.saturating_add(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+
+        if !c.is_whitespace() && c != ';' && start_line.is_none() {
+            start_line = Some(line);
+        }
+    }
+
+    if let (Some(l), Some(s)) = (start_line, src.get(start..)) {
+        statements.push((l, s.trim()));
+    }
+
+    statements
+}
+
+/// Assembles one statement (without its offset and label) at `address`,
+/// returning the program items and their bytes.
+fn statement(
+    source: &str,
+    address: usize,
+    labels: &Labels,
+) -> Result<(Vec<DataOrInstruction<'static>>, Vec<u8>), String> {
+    let (name, operands) = source
+        .split_once(char::is_whitespace)
+        .unwrap_or((source, ""));
+    let name = normalize(name);
+    let raw_operands = split_operands(operands);
+    let operands = resolve_labels(&name, &raw_operands, labels)?;
+    let operands: Vec<&str> = operands.iter().map(Cow::as_ref).collect();
+
+    match name.as_str() {
+        "data" => {
+            let [operand] = operands.as_slice() else {
+                return Err("`data` takes a byte string or an array of bytes".to_owned());
+            };
+            let bytes = data_bytes(operand)?;
+            Ok((
+                bytes
+                    .iter()
+                    .map(|&b| DataOrInstruction::ByteData(b))
+                    .collect(),
+                bytes,
+            ))
+        }
+        "byte" => {
+            let [operand] = operands.as_slice() else {
+                return Err("`byte` takes a single byte".to_owned());
+            };
+            let byte = integer(operand, Operand::U8)?.to_be_bytes()[15];
+            Ok((vec![DataOrInstruction::ByteData(byte)], vec![byte]))
+        }
+        "fill" => {
+            let [value, amount] = operands.as_slice() else {
+                return Err("`fill` takes a byte and an amount".to_owned());
+            };
+            reject_label(raw_operands.get(1).copied(), "the amount of `fill`")?;
+            let value = integer(value, Operand::U8)?.to_be_bytes()[15];
+            let amount = u16::try_from(integer(amount, Operand::U16)?).unwrap_or_default();
+
+            Ok((
+                vec![DataOrInstruction::Fill(value, amount)],
+                vec![value; amount.into()],
+            ))
+        }
+        "pushstr" => {
+            let [operand] = operands.as_slice() else {
+                return Err("`pushstr` takes a byte string".to_owned());
+            };
+            let bytes = byte_string(operand)?;
+            Ok((
+                bytes
+                    .iter()
+                    .map(|&b| DataOrInstruction::Instruction(Instruction::Pushi(b)))
+                    .collect(),
+                bytes
+                    .iter()
+                    .flat_map(|&b| [InstructionKind::Pushi as u8, b])
+                    .collect(),
+            ))
+        }
+        "align" => {
+            let [operand] = operands.as_slice() else {
+                return Err("`align` takes an alignment".to_owned());
+            };
+            reject_label(raw_operands.first().copied(), "the alignment of `align`")?;
+            let alignment = usize::try_from(integer(operand, Operand::U16)?).unwrap_or_default();
+            let padding = match address.checked_rem(alignment) {
+                None | Some(0) => 0,
+                Some(rem) => alignment.wrapping_sub(rem),
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            Ok((
+                vec![DataOrInstruction::Zeroed(padding as u16)],
+                vec![0; padding],
+            ))
+        }
+        _ => {
+            let kind = kinds()
+                .find(|&kind| kind.mnemonic() == name)
+                .ok_or_else(|| format!("`{name}` isn't a valid esoteric assembly instruction"))?;
+
+            let expected = kind.operands();
+            if operands.len() != expected.len() {
+                return Err(format!(
+                    "`{name}` takes {} operand(s), found {}",
+                    expected.len(),
+                    operands.len()
+                ));
+            }
+
+            let mut bytes = vec![kind as u8];
+            for (operand, &kind) in operands.iter().zip(expected) {
+                encode(operand, kind, &mut bytes)?;
+            }
+
+            let instruction = Decoder::new(&bytes, 0)
+                .decode()
+                .ok_or_else(|| format!("invalid operands for `{name}`"))?;
+
+            Ok((vec![DataOrInstruction::Instruction(instruction)], bytes))
+        }
+    }
+}
+
+/// Removes an `offset:` prefix from a statement.
+fn strip_offset(source: &str) -> &str {
+    source
+        .split_once(':')
+        .filter(|(offset, _)| integer(offset.trim(), Operand::U16).is_ok())
+        .map_or(source, |(_, rest)| rest.trim_start())
+}
+
+/// Splits a `label:` prefix off a statement.
+fn split_label(source: &str) -> (Option<&str>, &str) {
+    source
+        .split_once(':')
+        .filter(|(label, _)| is_identifier(label.trim_end()))
+        .map_or((None, source), |(label, rest)| {
+            (Some(label.trim_end()), rest.trim_start())
+        })
+}
+
+/// Returns whether `name` could be a label.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Splits an operand that's a label into the label and its `as` cast (if any).
+fn label(operand: &str) -> Option<(&str, Option<&str>)> {
+    let (name, cast) = operand
+        .split_once(" as ")
+        .map_or((operand, None), |(name, cast)| {
+            (name.trim_end(), Some(cast.trim()))
+        });
+
+    (is_identifier(name) && !matches!(name, "true" | "false" | "None")).then_some((name, cast))
+}
+
+/// Replaces the labels in the operands of the statement `name` with their addresses.
+fn resolve_labels<'a>(
+    name: &str,
+    operands: &[&'a str],
+    labels: &Labels,
+) -> Result<Vec<Cow<'a, str>>, String> {
+    // floats like `inf` and `NaN` look like labels, so they're left as they are
+    let kinds = kinds()
+        .find(|&kind| kind.mnemonic() == name)
+        .map_or(&[][..], InstructionKind::operands);
+
+    operands
+        .iter()
+        .enumerate()
+        .map(|(i, operand)| match kinds.get(i) {
+            Some(Operand::F64) => Ok(Cow::Borrowed(*operand)),
+            _ => resolve_label(operand, labels),
+        })
+        .collect()
+}
+
+/// Rejects a label as an operand that changes the size of the program
+/// (and so the addresses of the labels after it).
+fn reject_label(operand: Option<&str>, what: &str) -> Result<(), String> {
+    match operand.and_then(label) {
+        Some(_) => Err(format!("{what} can't be a label")),
+        None => Ok(()),
+    }
+}
+
+/// Replaces a label (optionally cast with `as`) with its address.
+fn resolve_label<'a>(operand: &'a str, labels: &Labels) -> Result<Cow<'a, str>, String> {
+    let Some((name, cast)) = label(operand) else {
+        return Ok(Cow::Borrowed(operand));
+    };
+    let address = *labels
+        .get(name)
+        .ok_or_else(|| format!("the label `{name}` isn't defined"))?;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let value = match cast {
+        None | Some("u16" | "i32") => i128::from(address),
+        Some("u8") => i128::from(address as u8),
+        Some("i8") => i128::from(address as i8),
+        Some("i16") => i128::from(address as i16),
+        Some(cast) => return Err(format!("can't cast a label to `{cast}`")),
+    };
+    Ok(Cow::Owned(value.to_string()))
+}
+
+/// The pseudo-instructions, which assemble to data instead of an instruction.
+const DIRECTIVES: [&str; 5] = ["data", "byte", "fill", "align", "pushstr"];
+
+/// Returns every mnemonic the assembler accepts, in lowercase.
+///
+/// These are the [pseudo-instructions](crate::esoteric_assembly) (`data`, `byte`, `fill`, `align` and `pushstr`)
+/// followed by the instructions in opcode order.
+/// Uppercase mnemonics are accepted too, but aren't listed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::assembly::mnemonics;
+/// assert_eq!(mnemonics()[..6], ["data", "byte", "fill", "align", "pushstr", "nop"]);
+/// assert!(mnemonics().contains(&"pushi"));
+/// ```
+#[must_use]
+pub fn mnemonics() -> &'static [&'static str] {
+    static NAMES: OnceLock<Vec<String>> = OnceLock::new();
+    static MNEMONICS: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+    MNEMONICS.get_or_init(|| {
+        let names = NAMES.get_or_init(|| kinds().map(InstructionKind::mnemonic).collect());
+        DIRECTIVES
+            .into_iter()
+            .chain(names.iter().map(String::as_str))
+            .collect()
+    })
+}
+
+/// Every kind of instruction, in opcode order.
+fn kinds() -> impl Iterator<Item = InstructionKind> {
+    (0..=u8::MAX).filter_map(InstructionKind::from_repr)
+}
+
+/// Splits operands at top-level commas.
+fn split_operands(operands: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut depth: usize = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in operands.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' => depth = depth.saturating_add(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                split.extend(operands.get(start..i).map(str::trim));
+                start = i.saturating_add(1);
+            }
+            _ => (),
+        }
+    }
+
+    if let Some(last) = operands.get(start..).map(str::trim) {
+        if !last.is_empty() || !split.is_empty() {
+            split.push(last);
+        }
+    }
+
+    split
+}
+
+/// Encodes an operand into bytes the way it's stored in memory.
+fn encode(operand: &str, kind: Operand, bytes: &mut Vec<u8>) -> Result<(), String> {
+    match kind {
+        Operand::Bool => match operand {
+            "true" => bytes.push(1),
+            "false" => bytes.push(0),
+            _ => return Err(format!("expected `true` or `false`, found `{operand}`")),
+        },
+        Operand::Choice => bytes.push(choice(operand)?),
+        Operand::Array => {
+            let values = if let Some((value, count)) = operand
+                .strip_prefix('[')
+                .and_then(|v| v.strip_suffix(']'))
+                .and_then(|v| v.split_once(';'))
+            {
+                let count = integer(count.trim(), Operand::U16)?;
+                vec![value.trim(); usize::try_from(count).unwrap_or_default()]
+            } else {
+                operand
+                    .strip_prefix('[')
+                    .and_then(|v| v.strip_suffix(']'))
+                    .map(split_operands)
+                    .ok_or_else(|| format!("expected an array, found `{operand}`"))?
+            };
+
+            if values.len() != 37 {
+                return Err(format!("expected 37 values, found {}", values.len()));
+            }
+            for value in values {
+                encode(value, Operand::I8, bytes)?;
+            }
+        }
+        Operand::F64 => {
+            let value: f64 = operand
+                .replace('_', "")
+                .replace("- ", "-")
+                .parse()
+                .map_err(|_| format!("expected a float, found `{operand}`"))?;
+            bytes.extend(value.to_bits().to_be_bytes());
+        }
+        Operand::U8 | Operand::I8 | Operand::U16 | Operand::I16 | Operand::I32 => {
+            let value = integer(operand, kind)?.to_be_bytes();
+            let size = kind.size() as usize;
+            bytes.extend(
+                value
+                    .get(value.len().saturating_sub(size)..)
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses an integer (or a byte literal) that fits in `kind`.
+fn integer(operand: &str, kind: Operand) -> Result<i128, String> {
+    let (negative, digits) = operand
+        .strip_prefix('-')
+        .map_or((false, operand), |digits| (true, digits.trim_start()));
+    let digits = digits.replace('_', "");
+
+    let value = if let Some(literal) = digits.strip_prefix("b'").and_then(|v| v.strip_suffix('\''))
+    {
+        match unescape(literal)?.as_slice() {
+            [byte] => Ok(i128::from(*byte)),
+            _ => Err(()),
+        }
+    } else if let Some(hex) = digits.strip_prefix("0x") {
+        i128::from_str_radix(hex, 16).map_err(drop)
+    } else if let Some(octal) = digits.strip_prefix("0o") {
+        i128::from_str_radix(octal, 8).map_err(drop)
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        i128::from_str_radix(binary, 2).map_err(drop)
+    } else {
+        digits.parse().map_err(drop)
+    }
+    .map_err(|()| format!("expected an integer, found `{operand}`"))?;
+    let value = if negative {
+        value.saturating_neg()
+    } else {
+        value
+    };
+
+    let (min, max) = match kind {
+        Operand::U8 => (0, i128::from(u8::MAX)),
+        Operand::I8 => (i128::from(i8::MIN), i128::from(i8::MAX)),
+        Operand::U16 => (0, i128::from(u16::MAX)),
+        Operand::I16 => (i128::from(i16::MIN), i128::from(i16::MAX)),
+        Operand::I32 => (i128::from(i32::MIN), i128::from(i32::MAX)),
+        Operand::Bool | Operand::Choice | Operand::Array | Operand::F64 => (0, 0),
+    };
+    if value < min || value > max {
+        return Err(format!(
+            "`{operand}` is out of range for a {kind:?} operand"
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Parses an illusion of choice (`None`, `Some(None)`, ..., `Some(Some(Some(Some(()))))`)
+/// and returns its byte representation.
+fn choice(operand: &str) -> Result<u8, String> {
+    let mut rest = operand.trim();
+    let mut depth: usize = 0;
+
+    while let Some(inner) = rest.strip_prefix("Some").map(str::trim_start) {
+        rest = inner
+            .strip_prefix('(')
+            .and_then(|v| v.strip_suffix(')'))
+            .ok_or_else(|| format!("invalid choice `{operand}`"))?
+            .trim();
+        depth = depth.saturating_add(1);
+    }
+
+    let value = match (depth, rest) {
+        (0, "None") => None,
+        (1, "None") => Some(None),
+        (2, "None") => Some(Some(None)),
+        (3, "None") => Some(Some(Some(None))),
+        (3, "()") => Some(Some(Some(Some(())))),
+        _ => return Err(format!("invalid choice `{operand}`")),
+    };
+
+    Ok(choice_to_byte(value))
+}
+
+/// Parses a byte string literal like `b"Hello\n\0"`.
+fn byte_string(operand: &str) -> Result<Vec<u8>, String> {
+    operand
+        .strip_prefix("b\"")
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a byte string like `b\"...\"`, found `{operand}`"))
+        .and_then(unescape)
+}
+
+/// Parses the operand of `data`, a byte string or an array of bytes like `[0x00, b'.', 65]`.
+fn data_bytes(operand: &str) -> Result<Vec<u8>, String> {
+    let Some(array) = operand.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+        return byte_string(operand);
+    };
+
+    split_operands(array)
+        .into_iter()
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| Ok(integer(byte, Operand::U8)?.to_be_bytes()[15]))
+        .collect()
+}
+
+/// Resolves the escapes of the inside of a byte (string) literal.
+fn unescape(literal: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(literal.len());
+    let mut chars = literal.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        let byte = match chars.next() {
+            Some('n') => b'\n',
+            Some('r') => b'\r',
+            Some('t') => b'\t',
+            Some('0') => b'\0',
+            Some('\\') => b'\\',
+            Some('\'') => b'\'',
+            Some('"') => b'"',
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                u8::from_str_radix(&hex, 16).map_err(|_| format!("invalid escape `\\x{hex}`"))?
+            }
+            other => return Err(format!("invalid escape `\\{}`", other.unwrap_or(' '))),
+        };
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
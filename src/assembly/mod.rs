@@ -1,6 +1,19 @@
 //! Assembly compiler for Esoteric VM.
 //!
-//! More info at [`esoteric_assembly`].
+//! More info at [`esoteric_assembly`] and [`assemble`].
+
+mod assembler;
+
+use std::fmt;
+
+use crate::{
+    instruction::{DataOrInstruction, Instruction},
+    utils::primes::is_fib_prime_or_semiprime_u16,
+};
+
+pub use assembler::{
+    assemble, assemble_with_listing, mnemonics, AssembleError, Listing, ListingLine,
+};
 
 #[doc(hidden)]
 #[allow(non_upper_case_globals)]
@@ -14,15 +27,57 @@ pub mod __instructions {
     pub const byte: instruction = instruction;
     pub const BYTE: instruction = instruction;
 
+    pub const align: instruction = instruction;
+    pub const ALIGN: instruction = instruction;
+
+    pub const pushstr: instruction = instruction;
+    pub const PUSHSTR: instruction = instruction;
+
     pub const nop: instruction = instruction;
     pub const NOP: instruction = instruction;
 
     pub const ldar: instruction = instruction;
     pub const LDAR: instruction = instruction;
 
+    pub const ldarl: instruction = instruction;
+    pub const LDARL: instruction = instruction;
+
+    pub const starl: instruction = instruction;
+    pub const STARL: instruction = instruction;
+
+    pub const memfilla: instruction = instruction;
+    pub const MEMFILLA: instruction = instruction;
+
+    pub const memcpy: instruction = instruction;
+    pub const MEMCPY: instruction = instruction;
+
+    pub const memset: instruction = instruction;
+    pub const MEMSET: instruction = instruction;
+
+    pub const shuffle: instruction = instruction;
+    pub const SHUFFLE: instruction = instruction;
+
     pub const sba: instruction = instruction;
     pub const SBA: instruction = instruction;
 
+    pub const swapab: instruction = instruction;
+    pub const SWAPAB: instruction = instruction;
+
+    pub const movab: instruction = instruction;
+    pub const MOVAB: instruction = instruction;
+
+    pub const movba: instruction = instruction;
+    pub const MOVBA: instruction = instruction;
+
+    pub const rotla: instruction = instruction;
+    pub const ROTLA: instruction = instruction;
+
+    pub const rotra: instruction = instruction;
+    pub const ROTRA: instruction = instruction;
+
+    pub const atodec: instruction = instruction;
+    pub const ATODEC: instruction = instruction;
+
     pub const clř: instruction = instruction;
     pub const CLŘ: instruction = instruction;
 
@@ -74,9 +129,24 @@ pub mod __instructions {
     pub const lenßa: instruction = instruction;
     pub const LENßA: instruction = instruction;
 
+    pub const lenßl: instruction = instruction;
+    pub const LENßL: instruction = instruction;
+
+    pub const jßeq: instruction = instruction;
+    pub const JßEQ: instruction = instruction;
+
     pub const ldidp: instruction = instruction;
     pub const LDIDP: instruction = instruction;
 
+    pub const checkdp: instruction = instruction;
+    pub const CHECKDP: instruction = instruction;
+
+    pub const ldinum: instruction = instruction;
+    pub const LDINUM: instruction = instruction;
+
+    pub const ldif: instruction = instruction;
+    pub const LDIF: instruction = instruction;
+
     pub const Ωchoiceset: instruction = instruction;
     pub const ΩCHOICESET: instruction = instruction;
 
@@ -110,6 +180,18 @@ pub mod __instructions {
     pub const subbl: instruction = instruction;
     pub const SUBBL: instruction = instruction;
 
+    pub const addil: instruction = instruction;
+    pub const ADDIL: instruction = instruction;
+
+    pub const subil: instruction = instruction;
+    pub const SUBIL: instruction = instruction;
+
+    pub const incl: instruction = instruction;
+    pub const INCL: instruction = instruction;
+
+    pub const decl: instruction = instruction;
+    pub const DECL: instruction = instruction;
+
     pub const mulbl: instruction = instruction;
     pub const MULBL: instruction = instruction;
 
@@ -131,15 +213,42 @@ pub mod __instructions {
     pub const xorbl: instruction = instruction;
     pub const XORBL: instruction = instruction;
 
+    pub const popcntl: instruction = instruction;
+    pub const POPCNTL: instruction = instruction;
+
+    pub const ctzl: instruction = instruction;
+    pub const CTZL: instruction = instruction;
+
+    pub const clzl: instruction = instruction;
+    pub const CLZL: instruction = instruction;
+
     pub const cmplb: instruction = instruction;
     pub const CMPLB: instruction = instruction;
 
+    pub const clampl: instruction = instruction;
+    pub const CLAMPL: instruction = instruction;
+
+    pub const cmpeqlb: instruction = instruction;
+    pub const CMPEQLB: instruction = instruction;
+
+    pub const cmpltlb: instruction = instruction;
+    pub const CMPLTLB: instruction = instruction;
+
+    pub const cmpgtlb: instruction = instruction;
+    pub const CMPGTLB: instruction = instruction;
+
     pub const tgflag: instruction = instruction;
     pub const TGFLAG: instruction = instruction;
 
     pub const clflag: instruction = instruction;
     pub const CLFLAG: instruction = instruction;
 
+    pub const pushflagclear: instruction = instruction;
+    pub const PUSHFLAGCLEAR: instruction = instruction;
+
+    pub const popflag: instruction = instruction;
+    pub const POPFLAG: instruction = instruction;
+
     pub const addf: instruction = instruction;
     pub const ADDF: instruction = instruction;
 
@@ -155,21 +264,54 @@ pub mod __instructions {
     pub const modf: instruction = instruction;
     pub const MODF: instruction = instruction;
 
+    pub const sqrtf: instruction = instruction;
+    pub const SQRTF: instruction = instruction;
+
+    pub const floorf: instruction = instruction;
+    pub const FLOORF: instruction = instruction;
+
+    pub const ceilf: instruction = instruction;
+    pub const CEILF: instruction = instruction;
+
+    pub const roundf: instruction = instruction;
+    pub const ROUNDF: instruction = instruction;
+
+    pub const ftol: instruction = instruction;
+    pub const FTOL: instruction = instruction;
+
+    pub const ltof: instruction = instruction;
+    pub const LTOF: instruction = instruction;
+
     pub const stackalloc: instruction = instruction;
     pub const STACKALLOC: instruction = instruction;
 
     pub const stackdealloc: instruction = instruction;
     pub const STACKDEALLOC: instruction = instruction;
 
+    pub const dup: instruction = instruction;
+    pub const DUP: instruction = instruction;
+
+    pub const swap: instruction = instruction;
+    pub const SWAP: instruction = instruction;
+
+    pub const stackclear: instruction = instruction;
+    pub const STACKCLEAR: instruction = instruction;
+
     pub const push: instruction = instruction;
     pub const PUSH: instruction = instruction;
 
     pub const pushi: instruction = instruction;
     pub const PUSHI: instruction = instruction;
 
+    pub const pushi16: instruction = instruction;
+    pub const PUSHI16: instruction = instruction;
+
     pub const pop: instruction = instruction;
     pub const POP: instruction = instruction;
 
+    pub const popmem: instruction = instruction;
+    pub const POPMEM: instruction = instruction;
+
     pub const popa: instruction = instruction;
     pub const POPA: instruction = instruction;
 
@@ -188,6 +330,12 @@ pub mod __instructions {
     pub const pushl: instruction = instruction;
     pub const PUSHL: instruction = instruction;
 
+    pub const sptol: instruction = instruction;
+    pub const SPTOL: instruction = instruction;
+
+    pub const eptol: instruction = instruction;
+    pub const EPTOL: instruction = instruction;
+
     pub const popf: instruction = instruction;
     pub const POPF: instruction = instruction;
 
@@ -221,24 +369,81 @@ pub mod __instructions {
     pub const fpopep: instruction = instruction;
     pub const FPOPEP: instruction = instruction;
 
+    pub const zapopep: instruction = instruction;
+    pub const ZAPOPEP: instruction = instruction;
+
     pub const dpopep: instruction = instruction;
     pub const DPOPEP: instruction = instruction;
 
+    pub const jmpl: instruction = instruction;
+    pub const JMPL: instruction = instruction;
+
+    pub const jmp: instruction = instruction;
+    pub const JMP: instruction = instruction;
+
+    pub const jz: instruction = instruction;
+    pub const JZ: instruction = instruction;
+
+    pub const jnz: instruction = instruction;
+    pub const JNZ: instruction = instruction;
+
+    pub const jf: instruction = instruction;
+    pub const JF: instruction = instruction;
+
+    pub const fjmpclear: instruction = instruction;
+    pub const FJMPCLEAR: instruction = instruction;
+
+    pub const call: instruction = instruction;
+    pub const CALL: instruction = instruction;
+
+    pub const ret: instruction = instruction;
+    pub const RET: instruction = instruction;
+
+    pub const halt: instruction = instruction;
+    pub const HALT: instruction = instruction;
+
+    pub const beqai: instruction = instruction;
+    pub const BEQAI: instruction = instruction;
+
+    pub const jmemeq: instruction = instruction;
+    pub const JMEMEQ: instruction = instruction;
+
     pub const getchar: instruction = instruction;
     pub const GETCHAR: instruction = instruction;
 
     pub const getline: instruction = instruction;
     pub const GETLINE: instruction = instruction;
 
+    pub const readnum: instruction = instruction;
+    pub const READNUM: instruction = instruction;
+
+    pub const getbyte: instruction = instruction;
+    pub const GETBYTE: instruction = instruction;
+
+    pub const ateof: instruction = instruction;
+    pub const ATEOF: instruction = instruction;
+
     pub const writechar: instruction = instruction;
     pub const WRITECHAR: instruction = instruction;
 
+    pub const putbyte: instruction = instruction;
+    pub const PUTBYTE: instruction = instruction;
+
+    pub const writenum: instruction = instruction;
+    pub const WRITENUM: instruction = instruction;
+
     pub const writelineß: instruction = instruction;
     pub const WRITELINEß: instruction = instruction;
 
     pub const writeline: instruction = instruction;
     pub const WRITELINE: instruction = instruction;
 
+    pub const writelineifflag: instruction = instruction;
+    pub const WRITELINEIFFLAG: instruction = instruction;
+
+    pub const writestack: instruction = instruction;
+    pub const WRITESTACK: instruction = instruction;
+
     pub const toggledebug: instruction = instruction;
     pub const TOGGLEDEBUG: instruction = instruction;
 
@@ -253,16 +458,71 @@ pub mod __instructions {
 
     pub const debugstackregion: instruction = instruction;
     pub const DEBUGSTACKREGION: instruction = instruction;
+
+    pub const showchoice: instruction = instruction;
+    pub const SHOWCHOICE: instruction = instruction;
 }
 
 /// Assembly compiler for esoteric VM.
 ///
 /// Input format: `<n>: <inst> <arg1?>, <arg2?>;`, with:
 /// - `<n>:` being an optional helper prefix (usually an integer
-///     to denote he instruction's location in memory),\
+///   to denote he instruction's location in memory),\
 /// - `<inst>` being the instruction, and \
 /// - `<argx>` being the argument (usually a number).
 ///
+/// Besides instructions, there are the directives
+/// - `data <bytes>`, which emits a byte string or an array of bytes
+///   (like `data [0x00, b'.', 65]`),
+/// - `byte <u8>`, which emits a single byte,
+/// - `fill <u8>, <u16>`, which emits the byte the given amount of times,
+/// - `align <u16>`, which pads with zero bytes up to the next address that's
+///   a multiple of the value when the program is loaded
+///   (nothing if the address already is one or the value is 0), and
+/// - `pushstr <bytes>`, which emits a `pushi` for every byte, in order.
+///   The last byte ends up on top of the stack, so `popmem` puts the bytes back
+///   in order and `pushß` takes them in reverse.
+///
+/// An instruction can also be prefixed with a label (`<label>:`, after the `<n>:` prefix),
+/// which can be used in operands as the `u16` address of the instruction.
+/// The addresses are counted from the start of the program, so they're only right
+/// if it's loaded at address 0. Using a label that isn't defined is an error.
+/// Programs with labels aren't constant and their operands are evaluated twice
+/// (once to know how big every instruction is, and once with the addresses).
+///
+/// ```rust
+/// # use esoteric_vm::{esoteric_assembly, Machine};
+/// let mut machine = Machine::default();
+///
+/// let assembly = esoteric_assembly! {
+///     pushi b'.';
+///     pop dot;
+///     ldidp dot;
+///     writeline hello;
+///     Ωtheendisnear;
+///     Ωskiptothechase;
+///
+///     hello: data b"Hello, world!\n\0";
+///     // a valid dot pointer
+///     align 28657;
+///     dot: byte 0;
+/// };
+///
+/// machine.load(&assembly, 0);
+/// machine.run();
+///
+/// assert_eq!(machine.reg_dp, 28657);
+/// assert!(!machine.flag);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use esoteric_vm::esoteric_assembly;
+/// let assembly = esoteric_assembly! {
+///     start: nop;
+///     writeline nowhere;
+/// };
+/// ```
+///
 /// # Examples
 ///
 /// ```rust
@@ -293,12 +553,24 @@ pub mod __instructions {
 macro_rules! esoteric_assembly {
     () => { [] as [$crate::instruction::DataOrInstruction; 0] };
 
-    ({} data $data:expr) => { $crate::instruction::DataOrInstruction::Data($data as &[u8]) };
-    ({} DATA $data:expr) => { $crate::instruction::DataOrInstruction::Data($data as &[u8]) };
+    ({} $label:ident : $($rest:tt)*) => { $crate::esoteric_assembly!({} $($rest)*) };
+
+    // `AsRef` takes byte strings and arrays of bytes like `[0x00, b'.', 65]`
+    ({} data $data:expr) => { $crate::instruction::DataOrInstruction::Data(::core::convert::AsRef::<[u8]>::as_ref(&$data)) };
+    ({} DATA $data:expr) => { $crate::instruction::DataOrInstruction::Data(::core::convert::AsRef::<[u8]>::as_ref(&$data)) };
 
     ({} byte $data:expr) => { $crate::instruction::DataOrInstruction::ByteData($data as u8) };
     ({} BYTE $data:expr) => { $crate::instruction::DataOrInstruction::ByteData($data as u8) };
 
+    ({} fill $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Fill($data0 as u8, $data1) };
+    ({} FILL $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Fill($data0 as u8, $data1) };
+
+    ({} align $data:expr) => { $crate::instruction::DataOrInstruction::Align($data) };
+    ({} ALIGN $data:expr) => { $crate::instruction::DataOrInstruction::Align($data) };
+
+    ({} pushstr $data:expr) => { $crate::instruction::DataOrInstruction::PushString($data as &[u8]) };
+    ({} PUSHSTR $data:expr) => { $crate::instruction::DataOrInstruction::PushString($data as &[u8]) };
+
     ({} nop) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Nop) };
     ({} NOP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Nop) };
 
@@ -308,9 +580,78 @@ macro_rules! esoteric_assembly {
     ({} ldar) => { compile_error!("missing argument for `ldar` instruction."); };
     ({} LDAR) => { compile_error!("missing argument for `ldar` instruction."); };
 
+    ({} ldarl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::LdarL) };
+    ({} LDARL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::LdarL) };
+
+    ({} starl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StarL) };
+    ({} STARL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StarL) };
+
+    ({} memfilla $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemFillA($data0, $data1)) };
+    ({} MEMFILLA $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemFillA($data0, $data1)) };
+
+    ({} memfilla) => { compile_error!("missing arguments for `memfilla` instruction."); };
+    ({} MEMFILLA) => { compile_error!("missing arguments for `memfilla` instruction."); };
+    ({} memfilla $data:expr) => { compile_error!("missing argument for `memfilla` instruction."); };
+    ({} MEMFILLA $data:expr) => { compile_error!("missing argument for `memfilla` instruction."); };
+
+    ({} memcpy $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemCpy($data0, $data1, $data2)) };
+    ({} MEMCPY $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemCpy($data0, $data1, $data2)) };
+
+    ({} memcpy) => { compile_error!("missing arguments for `memcpy` instruction."); };
+    ({} MEMCPY) => { compile_error!("missing arguments for `memcpy` instruction."); };
+    ({} memcpy $data:expr) => { compile_error!("missing arguments for `memcpy` instruction."); };
+    ({} MEMCPY $data:expr) => { compile_error!("missing arguments for `memcpy` instruction."); };
+    ({} memcpy $data0:expr, $data1:expr) => { compile_error!("missing argument for `memcpy` instruction."); };
+    ({} MEMCPY $data0:expr, $data1:expr) => { compile_error!("missing argument for `memcpy` instruction."); };
+
+    ({} memset $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemSet($data0, $data1, $data2)) };
+    ({} MEMSET $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MemSet($data0, $data1, $data2)) };
+
+    ({} memset) => { compile_error!("missing arguments for `memset` instruction."); };
+    ({} MEMSET) => { compile_error!("missing arguments for `memset` instruction."); };
+    ({} memset $data:expr) => { compile_error!("missing arguments for `memset` instruction."); };
+    ({} MEMSET $data:expr) => { compile_error!("missing arguments for `memset` instruction."); };
+    ({} memset $data0:expr, $data1:expr) => { compile_error!("missing argument for `memset` instruction."); };
+    ({} MEMSET $data0:expr, $data1:expr) => { compile_error!("missing argument for `memset` instruction."); };
+
+    ({} shuffle $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Shuffle($data0, $data1)) };
+    ({} SHUFFLE $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Shuffle($data0, $data1)) };
+
+    ({} shuffle) => { compile_error!("missing arguments for `shuffle` instruction."); };
+    ({} SHUFFLE) => { compile_error!("missing arguments for `shuffle` instruction."); };
+    ({} shuffle $data:expr) => { compile_error!("missing argument for `shuffle` instruction."); };
+    ({} SHUFFLE $data:expr) => { compile_error!("missing argument for `shuffle` instruction."); };
+
     ({} sba) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Sba) };
     ({} SBA) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Sba) };
 
+    ({} swapab) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SwapAB) };
+    ({} SWAPAB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SwapAB) };
+
+    ({} movab) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MovAB) };
+    ({} MOVAB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MovAB) };
+
+    ({} movba) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MovBA) };
+    ({} MOVBA) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MovBA) };
+
+    ({} rotla $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RotlA($data)) };
+    ({} ROTLA $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RotlA($data)) };
+
+    ({} rotla) => { compile_error!("missing argument for `rotla` instruction."); };
+    ({} ROTLA) => { compile_error!("missing argument for `rotla` instruction."); };
+
+    ({} rotra $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RotrA($data)) };
+    ({} ROTRA $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RotrA($data)) };
+
+    ({} rotra) => { compile_error!("missing argument for `rotra` instruction."); };
+    ({} ROTRA) => { compile_error!("missing argument for `rotra` instruction."); };
+
+    ({} atodec $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AToDec($data)) };
+    ({} ATODEC $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AToDec($data)) };
+
+    ({} atodec) => { compile_error!("missing argument for `atodec` instruction."); };
+    ({} ATODEC) => { compile_error!("missing argument for `atodec` instruction."); };
+
     ({} clř) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Clř) };
     ({} CLŘ) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Clř) };
 
@@ -408,12 +749,38 @@ macro_rules! esoteric_assembly {
     ({} lenßa) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Lenßa) };
     ({} LENßA) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Lenßa) };
 
+    ({} lenßl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Lenßl) };
+    ({} LENßL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Lenßl) };
+
+    ({} jßeq $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jßeq($data0, $data1)) };
+    ({} JßEQ $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jßeq($data0, $data1)) };
+
+    ({} jßeq) => { compile_error!("missing arguments for `jßeq` instruction."); };
+    ({} JßEQ) => { compile_error!("missing arguments for `jßeq` instruction."); };
+    ({} jßeq $data:expr) => { compile_error!("missing argument for `jßeq` instruction."); };
+    ({} JßEQ $data:expr) => { compile_error!("missing argument for `jßeq` instruction."); };
+
     ({} ldidp $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldidp($data)) };
     ({} LDIDP $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldidp($data)) };
 
     ({} ldidp) => { compile_error!("missing argument for `ldidp` instruction."); };
     ({} LDIDP) => { compile_error!("missing argument for `ldidp` instruction."); };
 
+    ({} checkdp) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CheckDp) };
+    ({} CHECKDP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CheckDp) };
+
+    ({} ldinum $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldinum($data)) };
+    ({} LDINUM $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldinum($data)) };
+
+    ({} ldinum) => { compile_error!("missing argument for `ldinum` instruction."); };
+    ({} LDINUM) => { compile_error!("missing argument for `ldinum` instruction."); };
+
+    ({} ldif $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldif(f64::to_bits($data))) };
+    ({} LDIF $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ldif(f64::to_bits($data))) };
+
+    ({} ldif) => { compile_error!("missing argument for `ldif` instruction."); };
+    ({} LDIF) => { compile_error!("missing argument for `ldif` instruction."); };
+
     ({} Ωchoiceset $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩChoiceSet($data)) };
     ({} ΩCHOICESET $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩChoiceSet($data)) };
 
@@ -429,8 +796,8 @@ macro_rules! esoteric_assembly {
     ({} Ωloseapolymorphicdesires) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩLoseAPolymorphicDesires) };
     ({} ΩLOSEAPOLYMORPHICDESIRES) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩLoseAPolymorphicDesires) };
 
-    ({} Ωpushpolymorphicdesires) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩPushPolyMorphicDesires) };
-    ({} ΩPUSHPOLYMORPHICDESIRES) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩPushPolyMorphicDesires) };
+    ({} Ωpushpolymorphicdesires) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩPushPolymorphicDesires) };
+    ({} ΩPUSHPOLYMORPHICDESIRES) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩPushPolymorphicDesires) };
 
     ({} Ωtheendisnear) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩTheEndIsNear) };
     ({} ΩTHEENDISNEAR) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ΩTheEndIsNear) };
@@ -456,6 +823,24 @@ macro_rules! esoteric_assembly {
     ({} subbl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SubBL) };
     ({} SUBBL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SubBL) };
 
+    ({} addil $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AddiL($data)) };
+    ({} ADDIL $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AddiL($data)) };
+
+    ({} addil) => { compile_error!("missing argument for `addil` instruction."); };
+    ({} ADDIL) => { compile_error!("missing argument for `addil` instruction."); };
+
+    ({} subil $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SubiL($data)) };
+    ({} SUBIL $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SubiL($data)) };
+
+    ({} subil) => { compile_error!("missing argument for `subil` instruction."); };
+    ({} SUBIL) => { compile_error!("missing argument for `subil` instruction."); };
+
+    ({} incl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::IncL) };
+    ({} INCL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::IncL) };
+
+    ({} decl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::DecL) };
+    ({} DECL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::DecL) };
+
     ({} mulbl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MulBL) };
     ({} MULBL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::MulBL) };
 
@@ -477,15 +862,42 @@ macro_rules! esoteric_assembly {
     ({} xorbl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::XorBL) };
     ({} XORBL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::XorBL) };
 
+    ({} popcntl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopcntL) };
+    ({} POPCNTL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopcntL) };
+
+    ({} ctzl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CtzL) };
+    ({} CTZL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CtzL) };
+
+    ({} clzl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClzL) };
+    ({} CLZL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClzL) };
+
     ({} cmplb) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpLB) };
     ({} CMPLB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpLB) };
 
+    ({} clampl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClampL) };
+    ({} CLAMPL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClampL) };
+
+    ({} cmpeqlb) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpEqLB) };
+    ({} CMPEQLB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpEqLB) };
+
+    ({} cmpltlb) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpLtLB) };
+    ({} CMPLTLB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpLtLB) };
+
+    ({} cmpgtlb) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpGtLB) };
+    ({} CMPGTLB) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CmpGtLB) };
+
     ({} tgflag) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::TgFlag) };
     ({} TGFLAG) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::TgFlag) };
 
     ({} clflag) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClFlag) };
     ({} CLFLAG) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ClFlag) };
 
+    ({} pushflagclear) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushFlagClear) };
+    ({} PUSHFLAGCLEAR) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushFlagClear) };
+
+    ({} popflag) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopFlag) };
+    ({} POPFLAG) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopFlag) };
+
     ({} addf $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AddF($data)) };
     ({} ADDF $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AddF($data)) };
 
@@ -516,6 +928,24 @@ macro_rules! esoteric_assembly {
     ({} modf) => { compile_error!("missing argument for `modf` instruction."); };
     ({} MODF) => { compile_error!("missing argument for `modf` instruction."); };
 
+    ({} sqrtf) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SqrtF) };
+    ({} SQRTF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SqrtF) };
+
+    ({} floorf) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FloorF) };
+    ({} FLOORF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FloorF) };
+
+    ({} ceilf) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CeilF) };
+    ({} CEILF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::CeilF) };
+
+    ({} roundf) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RoundF) };
+    ({} ROUNDF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::RoundF) };
+
+    ({} ftol) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FtoL) };
+    ({} FTOL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FtoL) };
+
+    ({} ltof) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::LtoF) };
+    ({} LTOF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::LtoF) };
+
     ({} stackalloc $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackAlloc($data)) };
     ({} STACKALLOC $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackAlloc($data)) };
 
@@ -528,6 +958,15 @@ macro_rules! esoteric_assembly {
     ({} stackdealloc) => { compile_error!("missing argument for `stackdealloc` instruction."); };
     ({} STACKDEALLOC) => { compile_error!("missing argument for `stackdealloc` instruction."); };
 
+    ({} dup) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackDup) };
+    ({} DUP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackDup) };
+
+    ({} swap) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackSwap) };
+    ({} SWAP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackSwap) };
+
+    ({} stackclear) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackClear) };
+    ({} STACKCLEAR) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::StackClear) };
+
     ({} push $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Push($data)) };
     ({} PUSH $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Push($data)) };
 
@@ -540,12 +979,26 @@ macro_rules! esoteric_assembly {
     ({} pushi) => { compile_error!("missing argument for `pushi` instruction."); };
     ({} PUSHI) => { compile_error!("missing argument for `pushi` instruction."); };
 
+    ({} pushi16 $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushiU16($data)) };
+    ({} PUSHI16 $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushiU16($data)) };
+
+    ({} pushi16) => { compile_error!("missing argument for `pushi16` instruction."); };
+    ({} PUSHI16) => { compile_error!("missing argument for `pushi16` instruction."); };
+
     ({} pop $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Pop($data)) };
     ({} POP $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Pop($data)) };
 
     ({} pop) => { compile_error!("missing argument for `pop` instruction."); };
     ({} POP) => { compile_error!("missing argument for `pop` instruction."); };
 
+    ({} popmem $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopMem($data0, $data1)) };
+    ({} POPMEM $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PopMem($data0, $data1)) };
+
+    ({} popmem) => { compile_error!("missing arguments for `popmem` instruction."); };
+    ({} POPMEM) => { compile_error!("missing arguments for `popmem` instruction."); };
+    ({} popmem $data:expr) => { compile_error!("missing argument for `popmem` instruction."); };
+    ({} POPMEM $data:expr) => { compile_error!("missing argument for `popmem` instruction."); };
+
     ({} popa) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Popa) };
     ({} POPA) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Popa) };
 
@@ -564,6 +1017,12 @@ macro_rules! esoteric_assembly {
     ({} pushl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushL) };
     ({} PUSHL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PushL) };
 
+    ({} sptol) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SpToL) };
+    ({} SPTOL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::SpToL) };
+
+    ({} eptol) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::EpToL) };
+    ({} EPTOL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::EpToL) };
+
     ({} popf) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Popf) };
     ({} POPF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Popf) };
 
@@ -597,18 +1056,99 @@ macro_rules! esoteric_assembly {
     ({} fpopep) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Fpopep) };
     ({} FPOPEP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Fpopep) };
 
+    ({} zapopep) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Zapopep) };
+    ({} ZAPOPEP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Zapopep) };
+
     ({} dpopep) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Dpopep) };
     ({} DPOPEP) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Dpopep) };
 
+    ({} jmpl) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::JmpL) };
+    ({} JMPL) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::JmpL) };
+
+    ({} jmp $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jmp($data)) };
+    ({} JMP $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jmp($data)) };
+
+    ({} jmp) => { compile_error!("missing argument for `jmp` instruction."); };
+    ({} JMP) => { compile_error!("missing argument for `jmp` instruction."); };
+
+    ({} jz $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jz($data)) };
+    ({} JZ $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jz($data)) };
+
+    ({} jz) => { compile_error!("missing argument for `jz` instruction."); };
+    ({} JZ) => { compile_error!("missing argument for `jz` instruction."); };
+
+    ({} jnz $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jnz($data)) };
+    ({} JNZ $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jnz($data)) };
+
+    ({} jnz) => { compile_error!("missing argument for `jnz` instruction."); };
+    ({} JNZ) => { compile_error!("missing argument for `jnz` instruction."); };
+
+    ({} jf $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jf($data)) };
+    ({} JF $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Jf($data)) };
+
+    ({} jf) => { compile_error!("missing argument for `jf` instruction."); };
+    ({} JF) => { compile_error!("missing argument for `jf` instruction."); };
+
+    ({} fjmpclear $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FjmpClear($data)) };
+    ({} FJMPCLEAR $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::FjmpClear($data)) };
+
+    ({} fjmpclear) => { compile_error!("missing argument for `fjmpclear` instruction."); };
+    ({} FJMPCLEAR) => { compile_error!("missing argument for `fjmpclear` instruction."); };
+
+    ({} call $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Call($data)) };
+    ({} CALL $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Call($data)) };
+
+    ({} call) => { compile_error!("missing argument for `call` instruction."); };
+    ({} CALL) => { compile_error!("missing argument for `call` instruction."); };
+
+    ({} ret) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ret) };
+    ({} RET) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Ret) };
+
+    ({} halt) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Halt) };
+    ({} HALT) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::Halt) };
+
+    ({} beqai $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::BeqAi($data0, $data1)) };
+    ({} BEQAI $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::BeqAi($data0, $data1)) };
+
+    ({} beqai) => { compile_error!("missing arguments for `beqai` instruction."); };
+    ({} BEQAI) => { compile_error!("missing arguments for `beqai` instruction."); };
+    ({} beqai $data:expr) => { compile_error!("missing argument for `beqai` instruction."); };
+    ({} BEQAI $data:expr) => { compile_error!("missing argument for `beqai` instruction."); };
+
+    ({} jmemeq $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::JmemEq($data0, $data1, $data2)) };
+    ({} JMEMEQ $data0:expr, $data1:expr, $data2:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::JmemEq($data0, $data1, $data2)) };
+
+    ({} jmemeq) => { compile_error!("missing arguments for `jmemeq` instruction."); };
+    ({} JMEMEQ) => { compile_error!("missing arguments for `jmemeq` instruction."); };
+    ({} jmemeq $data:expr) => { compile_error!("missing arguments for `jmemeq` instruction."); };
+    ({} JMEMEQ $data:expr) => { compile_error!("missing arguments for `jmemeq` instruction."); };
+    ({} jmemeq $data0:expr, $data1:expr) => { compile_error!("missing argument for `jmemeq` instruction."); };
+    ({} JMEMEQ $data0:expr, $data1:expr) => { compile_error!("missing argument for `jmemeq` instruction."); };
+
     ({} getchar) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetChar) };
     ({} GETCHAR) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetChar) };
 
     ({} getline) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetLine) };
     ({} GETLINE) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetLine) };
 
+    ({} readnum) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ReadNum) };
+    ({} READNUM) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ReadNum) };
+
+    ({} getbyte) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetByte) };
+    ({} GETBYTE) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::GetByte) };
+
+    ({} ateof) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AtEof) };
+    ({} ATEOF) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::AtEof) };
+
     ({} writechar) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteChar) };
     ({} WRITECHAR) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteChar) };
 
+    ({} putbyte) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PutByte) };
+    ({} PUTBYTE) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::PutByte) };
+
+    ({} writenum) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteNum) };
+    ({} WRITENUM) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteNum) };
+
     ({} writelineß) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteLineß) };
     ({} WRITELINEß) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteLineß) };
 
@@ -618,6 +1158,20 @@ macro_rules! esoteric_assembly {
     ({} writeline) => { compile_error!("missing argument for `writeline` instruction."); };
     ({} WRITELINE) => { compile_error!("missing argument for `writeline` instruction."); };
 
+    ({} writelineifflag $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteLineIfFlag($data)) };
+    ({} WRITELINEIFFLAG $data:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteLineIfFlag($data)) };
+
+    ({} writelineifflag) => { compile_error!("missing argument for `writelineifflag` instruction."); };
+    ({} WRITELINEIFFLAG) => { compile_error!("missing argument for `writelineifflag` instruction."); };
+
+    ({} writestack $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteStack($data0, $data1)) };
+    ({} WRITESTACK $data0:expr, $data1:expr) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::WriteStack($data0, $data1)) };
+
+    ({} writestack) => { compile_error!("missing arguments for `writestack` instruction."); };
+    ({} WRITESTACK) => { compile_error!("missing arguments for `writestack` instruction."); };
+    ({} writestack $data:expr) => { compile_error!("missing argument for `writestack` instruction."); };
+    ({} WRITESTACK $data:expr) => { compile_error!("missing argument for `writestack` instruction."); };
+
     ({} toggledebug) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ToggleDebug) };
     ({} TOGGLEDEBUG) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ToggleDebug) };
 
@@ -643,6 +1197,8 @@ macro_rules! esoteric_assembly {
     ({} debugstackregion $data:expr) => { compile_error!("missing argument for `debugstackregion` instruction."); };
     ({} DEBUGSTACKREGION $data:expr) => { compile_error!("missing argument for `debugstackregion` instruction."); };
 
+    ({} showchoice) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ShowChoice) };
+    ({} SHOWCHOICE) => { $crate::instruction::DataOrInstruction::Instruction($crate::instruction::Instruction::ShowChoice) };
 
     ({} $($trash:tt)*) => { compile_error!(concat!("`", stringify!($($trash)*), "` isn't a valid esoteric assembly instruction")) };
 
@@ -657,4 +1213,108 @@ macro_rules! esoteric_assembly {
         )* ]
     }};
 
+    // with labels (`$first` is the label if there's a `$name`, the instruction otherwise)
+    ($($($n:literal:)? $first:ident $(: $name:ident)? $($value:expr),*);* $(;)?) => {{
+        $(
+            #[cfg(not(any(debug_assertions, not(debug_assertions))))] // never compile
+            use $crate::assembly::__instructions::$first;
+            $(
+                #[cfg(not(any(debug_assertions, not(debug_assertions))))] // never compile
+                use $crate::assembly::__instructions::$name;
+            )?
+        )*
+
+        #[allow(unused_assignments)]
+        let program = {
+            // first pass: every label is 0, which doesn't change the size of anything
+            $($( let $first: u16 = { let _ = stringify!($name); 0 }; )?)*
+            let sizes = [ $(
+                $crate::esoteric_assembly!({} $first $(: $name)? $($value),*),
+            )* ];
+
+            // second pass: every label is the address of its instruction
+            let mut sizes = sizes.iter();
+            let mut address: u16 = 0;
+            $(
+                $( let $first: u16 = { let _ = stringify!($name); address }; )?
+                address = address.wrapping_add(sizes.next().map_or(0, |item| item.loaded_len(address)));
+            )*
+
+            [ $(
+                $crate::esoteric_assembly!({} $first $(: $name)? $($value),*),
+            )* ]
+        };
+        program
+    }};
+
+}
+
+/// A warning about a likely mistake in an assembled program.
+///
+/// Returned by [`validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Warning {
+    /// The program uses IO instructions but never loads a valid dot pointer
+    /// with `ldidp`, so every IO operation would be cancelled and set the flag.
+    MissingDotPointer,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDotPointer => f.write_str(
+                "IO instructions are used but the dot pointer is never set with `ldidp`",
+            ),
+        }
+    }
+}
+
+/// Checks an assembled program for common mistakes.
+///
+/// This is a best-effort static lint, it doesn't run the program,
+/// so it can't follow computed addresses or know which instructions
+/// are actually reachable.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{assembly::{validate, Warning}, esoteric_assembly};
+/// let asm = esoteric_assembly! {
+///     0: writeline 5;
+///     3: Ωtheendisnear;
+///     4: Ωskiptothechase;
+///     5: data b"Hello, world!\n\0";
+/// };
+///
+/// assert_eq!(validate(&asm), [Warning::MissingDotPointer]);
+/// ```
+#[must_use]
+pub fn validate(program: &[DataOrInstruction]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    let instructions = || {
+        program.iter().filter_map(|v| match v {
+            DataOrInstruction::Instruction(instruction) => Some(instruction),
+            DataOrInstruction::ByteData(_)
+            | DataOrInstruction::Data(_)
+            | DataOrInstruction::Zeroed(_)
+            | DataOrInstruction::Fill(_, _)
+            | DataOrInstruction::Align(_)
+            | DataOrInstruction::PushString(_) => None,
+        })
+    };
+
+    // `getchar` is the only IO instruction that doesn't check the dot pointer
+    let uses_io = instructions()
+        .any(|instruction| instruction.is_io() && !matches!(instruction, Instruction::GetChar));
+    let sets_dot_pointer = instructions().any(|instruction| {
+        matches!(instruction, Instruction::Ldidp(data) if is_fib_prime_or_semiprime_u16(*data))
+    });
+
+    if uses_io && !sets_dot_pointer {
+        warnings.push(Warning::MissingDotPointer);
+    }
+
+    warnings
 }
@@ -2,6 +2,8 @@
 //!
 //! More info at [`Instruction`].
 
+use std::fmt;
+
 use strum::{EnumDiscriminants, FromRepr};
 
 /// An instruction.
@@ -10,6 +12,10 @@ use strum::{EnumDiscriminants, FromRepr};
 ///
 /// This `enum` is not stored directly into VM memory.
 /// The [`InstructionKind`] and the arguments, however, are.
+///
+/// Every variant's discriminant is its opcode. They're written out so that
+/// adding an instruction doesn't change the opcodes of the existing ones:
+/// new instructions take the next unused number, wherever they're declared.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, EnumDiscriminants)]
 #[strum_discriminants(name(InstructionKind))]
@@ -18,106 +24,200 @@ use strum::{EnumDiscriminants, FromRepr};
 pub enum Instruction {
     /// No operation
     #[default]
-    Nop,
+    Nop = 0,
 
     /// Load A (rotate left)
     ///
     /// ```rust,ignore
     /// memory[data].rotate_left(1) // note that rotate left isn't the same as shift left (<<)
     /// ```
-    Ldar(u16),
+    Ldar(u16) = 1,
+    /// Load A from the address in register L
+    ///
+    /// This is a plain load, the byte isn't rotated.
+    /// Sets the flag if register L points past the end of memory (65535).
+    ///
+    /// ```rust,ignore
+    /// reg_a = memory[reg_L]
+    /// ```
+    LdarL = 99,
+    /// Store A at the address in register L
+    ///
+    /// Sets the flag if register L points past the end of memory (65535).
+    ///
+    /// ```rust,ignore
+    /// memory[reg_L] = reg_a
+    /// ```
+    StarL = 100,
+    /// Fill `data1` bytes of memory starting at `data0` with register A,
+    /// wrapping around to the start of memory when the range goes past the end
+    ///
+    /// ```rust,ignore
+    /// memory[data0..data0 + data1].fill(reg_a)
+    /// ```
+    MemFillA(u16, u16) = 109,
+    /// Copy `data2` bytes of memory from `data1` to `data0` (the ranges may overlap),
+    /// wrapping around to the start of memory when a range goes past the end
+    ///
+    /// ```rust,ignore
+    /// ptr::copy(&memory[data1..data1 + data2], &mut memory[data0..data0 + data2], data2)
+    /// ```
+    MemCpy(u16, u16, u16) = 131,
+    /// Fill `data2` bytes of memory starting at `data0` with `data1`,
+    /// wrapping around to the start of memory when the range goes past the end
+    ///
+    /// ```rust,ignore
+    /// memory[data0..data0 + data2].fill(data1)
+    /// ```
+    MemSet(u16, u8, u16) = 132,
+    /// Shuffle `data1` bytes of memory starting at `data0` with the seedable
+    /// pseudorandom number generator ([`Machine::rng_state`](crate::Machine::rng_state)),
+    /// sets the flag and doesn't shuffle if the range would go past the end of memory
+    ///
+    /// ```rust,ignore
+    /// memory[data0..data0 + data1].shuffle(rng)
+    /// ```
+    Shuffle(u16, u16) = 110,
     /// Sign of register B to register A
     ///
     /// ```rust,ignore
     /// reg_a = reg_b.signum() // 0: zero, 1: positive, 255: negative
     /// ```
-    Sba,
+    Sba = 2,
+    /// Swap register A with the low byte of register B
+    ///
+    /// The high byte of register B is preserved.
+    ///
+    /// ```rust,ignore
+    /// let [high, low] = reg_b.to_be_bytes();
+    /// reg_b = i16::from_be_bytes([high, reg_a]);
+    /// reg_a = low
+    /// ```
+    SwapAB = 98,
+    /// Move register A to register B
+    ///
+    /// Register A is zero-extended, so register B is never negative afterwards.
+    ///
+    /// ```rust,ignore
+    /// reg_b = reg_a as i16
+    /// ```
+    MovAB = 126,
+    /// Move the low byte of register B to register A
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_b as u8
+    /// ```
+    MovBA = 127,
+    /// Rotate register A left by the immediate (modulo 8)
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_a.rotate_left(data % 8)
+    /// ```
+    RotlA(u8) = 86,
+    /// Rotate register A right by the immediate (modulo 8)
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_a.rotate_right(data % 8)
+    /// ```
+    RotrA(u8) = 87,
+    /// Write register A as three decimal digits (zero-padded) to memory
+    ///
+    /// Sets the flag and doesn't write if the digits don't fit before the end of memory.
+    ///
+    /// ```rust,ignore
+    /// memory[data..(data + 3)] = format!("{reg_a:03}")
+    /// ```
+    AToDec(u16) = 97,
 
     /// Clear ř
     ///
     /// ```rust,ignore
     /// reg_ř.fill(0)
     /// ```
-    Clř,
+    Clř = 3,
     /// Dump ř to memory
     ///
     /// ```rust,ignore
     /// memory[data] = reg_ř // indexes more than 1 byte of memory, this is pseudocode
     /// ```
-    Dumpř(u16),
+    Dumpř(u16) = 4,
     /// Move a value from ř to register A
     ///
     /// ```rust,ignore
     /// reg_a = reg_ř[data] // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Movař(u8),
+    Movař(u8) = 5,
     /// Set value in ř
     ///
     /// ```rust,ignore
     /// reg_ř[data0] = memory[data1] // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Setř(u8, u16),
+    Setř(u8, u16) = 6,
     /// Set immediate value in ř
     ///
     /// ```rust,ignore
     /// reg_ř[data0] = data1 // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Setiř(u8, i8),
+    Setiř(u8, i8) = 7,
     /// Load ř
     ///
     /// ```rust,ignore
     /// reg_ř = memory[data] // indexes more than 1 byte of memory, this is pseudocode
     /// ```
-    Ldř(u16),
+    Ldř(u16) = 8,
     /// Load immediate ř
     ///
     /// ```rust,ignore
     /// reg_ř = data
     /// ```
-    Ldiř([i8; 37]),
+    Ldiř([i8; 37]) = 9,
 
     /// Clear ß
     ///
     /// ```rust,ignore
     /// reg_ß = empty_string(),
     /// ```
-    Clß,
+    Clß = 10,
     /// Dump ß to memory
     ///
     /// ```rust,ignore
     /// memory[data] = reg_ß // indexes more than 1 byte of memory, this is pseudocode
     /// ```
-    Dumpß(u16),
-    /// Write a value from ß to memory
+    Dumpß(u16) = 11,
+    /// Write a value from ß to memory and register A
+    ///
+    /// Sets the flag and changes nothing if `data1` is out of bounds of register ß
+    /// or `data0` is past the end of memory (65535).
     ///
     /// ```rust,ignore
     /// memory[data0] = reg_ß[data1] // arrays can't be indexed by a u8, this is pseudocode
+    /// reg_a = reg_ß[data1]
     /// ```
-    Writeß(u16, u8),
+    Writeß(u16, u8) = 12,
     /// Move a value from ß to register A
     ///
     /// ```rust,ignore
     /// reg_a = reg_ß[data] // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Movaß(u8),
+    Movaß(u8) = 13,
     /// Set value in ß
     ///
     /// ```rust,ignore
     /// reg_ß[data1] = memory[data0] // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Setß(u16, u8),
+    Setß(u16, u8) = 14,
     /// Set immediate value in ß
     ///
     /// ```rust,ignore
     /// reg_ß[data1] = data0 // arrays can't be indexed by a u8, this is pseudocode
     /// ```
-    Setiß(u8, u8),
-    /// Load ß
+    Setiß(u8, u8) = 15,
+    /// Load ß, sets the flag and leaves ß empty if the memory isn't valid UTF-8
     ///
     /// ```rust,ignore
     /// reg_ß = memory[data] // indexes 256 bytes of memory, this is pseudocode
     /// ```
-    Ldß(u16),
+    Ldß(u16) = 16,
     /// Push to ß from stack (can't go over maximum length)
     ///
     /// ```rust,ignore
@@ -125,19 +225,39 @@ pub enum Instruction {
     ///     flag = true
     /// }
     /// ```
-    Pushß,
+    Pushß = 17,
     /// Pop ß to stack
     ///
     /// ```rust,ignore
     /// stack.push(reg_ß.pop())
     /// ```
-    Popß,
+    Popß = 18,
     /// Length of ß to register A (in bytes)
     ///
     /// ```rust,ignore
     /// reg_a = regß.len()
     /// ```
-    Lenßa,
+    Lenßa = 19,
+    /// Length of ß to register L (in bytes)
+    ///
+    /// Unlike [`Lenßa`](Self::Lenßa), this doesn't truncate the length to a byte.
+    ///
+    /// ```rust,ignore
+    /// reg_L = reg_ß.len()
+    /// ```
+    Lenßl = 101,
+    /// Jump to the second operand if ß is equal to the null terminated string at the first operand
+    ///
+    /// If there is no null byte before the end of memory, it doesn't jump and sets the flag.
+    ///
+    /// ```rust,ignore
+    /// match c_string(memory[data0]) {
+    ///     Some(str) if reg_ß == str => reg_ep = data1,
+    ///     Some(_) => (),
+    ///     None => flag = true,
+    /// }
+    /// ```
+    Jßeq(u16, u16) = 88,
 
     /// Load immediate dot pointer
     ///
@@ -150,46 +270,67 @@ pub enum Instruction {
     ///     reg_dp = data
     /// }
     /// ```
-    Ldidp(u16),
+    Ldidp(u16) = 20,
+    /// Check if the dot pointer points to a `.`, which IO instructions need
+    ///
+    /// ```rust,ignore
+    /// flag = memory[reg_dp] != b'.'
+    /// ```
+    CheckDp = 93,
+    /// Load immediate to number register
+    ///
+    /// ```rust,ignore
+    /// num_reg = data
+    /// ```
+    Ldinum(i32) = 84,
+    /// Load immediate to register F
+    ///
+    /// The data is the bit pattern of the float (see [`f64::to_bits`]),
+    /// so this is a 9-byte instruction. The assembly takes the float itself.
+    ///
+    /// ```rust,ignore
+    /// reg_f = f64::from_bits(data)
+    /// ```
+    Ldif(u64) = 94,
 
     /// Set the `reg_Ω.illusion_of_choice` to the specified value
     ///
     /// ```rust,ignore
     /// reg_Ω.illusion_of_choice = data
     /// ```
-    ΩChoiceSet(Option<Option<Option<Option<()>>>>),
+    ΩChoiceSet(Option<Option<Option<Option<()>>>>) = 21,
     /// Write the `reg_Ω.illusion_of_choice` to register A (it's 0, note: technically it isn't 0 but that's none of anyone's business, which makes it a good way to clear the A register)
     ///
     /// ```rust,ignore
     /// reg_a = 0
     /// ```
-    ΩChoiceGetA,
+    ΩChoiceGetA = 22,
 
     /// Increase polymorphic desires by register A's value (if it overflows, then it just stays at `u64::MAX`, which is saturating addition)
     ///
     /// ```rust,ignore
     /// reg_Ω.polymorphic_desires += reg_a
     /// ```
-    ΩGainAPolymorphicDesires,
+    ΩGainAPolymorphicDesires = 23,
     /// Decrease polymorphic desires by register A's value (if it overflows, then it just stays at 0, which is saturating subtraction)
     ///
     /// ```rust,ignore
     /// reg_Ω.polymorphic_desires -= reg_a
     /// ```
-    ΩLoseAPolymorphicDesires,
+    ΩLoseAPolymorphicDesires = 24,
     /// Push the amount of polymorphic desires onto stack
     ///
     /// ```rust,ignore
     /// stack.push(reg_Ω.polymorphic_desires)
     /// ```
-    ΩPushPolymorphicDesires,
+    ΩPushPolymorphicDesires = 25,
 
     /// Create the feeling of impending doom (and you can't cancel that)
     ///
     /// ```rust,ignore
     /// reg_Ω.feeling_of_impending_doom = true
     /// ```
-    ΩTheEndIsNear,
+    ΩTheEndIsNear = 26,
     /// If there is the feeling of impending doom, exit the program already (with the exit code being the value of the number register).
     ///
     /// ```rust,ignore
@@ -197,7 +338,7 @@ pub enum Instruction {
     ///     abort_program(num_reg)
     /// }
     /// ```
-    ΩSkipToTheChase,
+    ΩSkipToTheChase = 27,
 
     /// Make the machine sentient (it isn't actually a sentient being, or is it?)
     ///
@@ -208,14 +349,14 @@ pub enum Instruction {
     ///     resist() // resists the change and it doesn't happen
     /// }
     /// ```
-    ΩSetSentience(bool),
+    ΩSetSentience(bool) = 28,
 
     /// Turn the paperclip production on/off
     ///
     /// ```rust,ignore
     /// reg_Ω.should_make_infinite_paperclips = data
     /// ```
-    ΩSetPaperclipProduction(bool),
+    ΩSetPaperclipProduction(bool) = 29,
 
     // ARITHMETIC
     /// Add register B to register L
@@ -223,66 +364,130 @@ pub enum Instruction {
     /// ```rust,ignore
     /// reg_L += transmute(reg_b) // transmute to u16
     /// if overflow {
-    ///     flag = true
+    ///     flag = true // or halt in trap mode
     /// }
     /// ```
-    AddBL,
+    AddBL = 30,
     /// Subtract register B from register L
     ///
     /// ```rust,ignore
     /// reg_L -= transmute(reg_b) // transmute to u16
     /// if overflow {
-    ///     flag = true
+    ///     flag = true // or halt in trap mode
+    /// }
+    /// ```
+    SubBL = 31,
+    /// Add immediate value to register L
+    ///
+    /// ```rust,ignore
+    /// reg_L += data
+    /// if overflow {
+    ///     flag = true // or halt in trap mode
+    /// }
+    /// ```
+    AddiL(i16) = 105,
+    /// Subtract immediate value from register L
+    ///
+    /// ```rust,ignore
+    /// reg_L -= data
+    /// if overflow {
+    ///     flag = true // or halt in trap mode
+    /// }
+    /// ```
+    SubiL(i16) = 106,
+    /// Increment register L
+    ///
+    /// ```rust,ignore
+    /// reg_L += 1
+    /// if overflow {
+    ///     flag = true // or halt in trap mode
     /// }
     /// ```
-    SubBL,
+    IncL = 128,
+    /// Decrement register L
+    ///
+    /// ```rust,ignore
+    /// reg_L -= 1
+    /// if overflow {
+    ///     flag = true // or halt in trap mode
+    /// }
+    /// ```
+    DecL = 129,
     /// Multiply register B with register L to register L
     ///
     /// ```rust,ignore
     /// reg_L *= transmute(reg_b) // transmute to u16
     /// if overflow {
-    ///     flag = true
+    ///     flag = true // or halt in trap mode
     /// }
     /// ```
-    MulBL,
+    MulBL = 32,
     /// Divide register L with register B to register L
     ///
     /// ```rust,ignore
-    /// reg_L /= transmute(reg_b) // transmute to u16
+    /// if reg_b == 0 {
+    ///     flag = true // or halt in trap mode, reg_L is unchanged
+    /// } else {
+    ///     reg_L /= transmute(reg_b) // transmute to u16
+    ///     flag = false
+    /// }
     /// ```
-    DivBL,
+    DivBL = 33,
     /// Modulo register L with register B
     ///
     /// ```rust,ignore
-    /// reg_L %= transmute(reg_b) // transmute to u16
+    /// if reg_b == 0 {
+    ///     flag = true // or halt in trap mode, reg_L is unchanged
+    /// } else {
+    ///     reg_L %= transmute(reg_b) // transmute to u16
+    ///     flag = false
+    /// }
     /// ```
-    ModBL,
+    ModBL = 34,
 
     /// Bitwise NOT register L
     ///
     /// ```rust,ignore
     /// reg_L = !reg_L
     /// ```
-    NotL,
+    NotL = 35,
 
     /// Bitwise AND register B and register L to register L
     ///
     /// ```rust,ignore
     /// reg_L &= reg_b
     /// ```
-    AndBL,
+    AndBL = 36,
     /// Bitwise OR register B and register L to register L
     ///
     /// ```rust,ignore
     /// reg_L |= reg_b
     /// ```
-    OrBL,
+    OrBL = 37,
     /// Bitwise AND register B and register L to register L
     ///
     /// ```rust,ignore
     /// reg_L ^= reg_b
     /// ```
-    XorBL,
+    XorBL = 38,
+    /// Count set bits of register L to register A
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_L.count_ones()
+    /// ```
+    PopcntL = 111,
+    /// Count trailing zeros of register L to register A (16 if it's 0)
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_L.trailing_zeros()
+    /// ```
+    CtzL = 112,
+    /// Count leading zeros of register L to register A (16 if it's 0)
+    ///
+    /// ```rust,ignore
+    /// reg_a = reg_L.leading_zeros()
+    /// ```
+    ClzL = 113,
 
     /// Compare register B and register L to register B
     ///
@@ -310,51 +515,159 @@ pub enum Instruction {
     ///     }
     /// }
     /// ```
-    CmpLB,
+    CmpLB = 39,
+    /// Clamp register L into the range of [`i16`]
+    ///
+    /// This is the same saturation [`CmpLB`](Self::CmpLB) does before comparing.
+    ///
+    /// ```rust,ignore
+    /// if reg_L > 32767 { // i16::MAX
+    ///     reg_L = i16::MAX;
+    ///     flag = true;
+    /// }
+    /// ```
+    ClampL = 89,
+    /// Compare register L and register B for equality
+    ///
+    /// Sets the flag if register L is equal to register B (compared as integers,
+    /// without converting either register) and clears it otherwise.
+    /// Neither register is changed.
+    ///
+    /// ```rust,ignore
+    /// flag = (reg_L as i32) == (reg_b as i32)
+    /// ```
+    CmpEqLB = 119,
+    /// Compare if register L is less than register B
+    ///
+    /// Sets the flag if register L is less than register B (compared as integers,
+    /// without converting either register) and clears it otherwise.
+    /// Neither register is changed.
+    ///
+    /// ```rust,ignore
+    /// flag = (reg_L as i32) < (reg_b as i32)
+    /// ```
+    CmpLtLB = 120,
+    /// Compare if register L is greater than register B
+    ///
+    /// Sets the flag if register L is greater than register B (compared as integers,
+    /// without converting either register) and clears it otherwise.
+    /// Neither register is changed.
+    ///
+    /// ```rust,ignore
+    /// flag = (reg_L as i32) > (reg_b as i32)
+    /// ```
+    CmpGtLB = 121,
 
     /// Toggle flag
     ///
     /// ```rust,ignore
     /// flag = !flag
     /// ```
-    TgFlag,
+    TgFlag = 40,
     /// Clear flag
     ///
     /// ```rust,ignore
     /// flag = false
     /// ```
-    ClFlag,
+    ClFlag = 41,
+    /// Push flag and clear it
+    ///
+    /// Useful for saving the flag of the caller
+    /// before an operation that could change it.
+    ///
+    /// ```rust,ignore
+    /// stack.push_byte(flag as u8);
+    /// flag = false
+    /// ```
+    PushFlagClear = 90,
+    /// Pop flag
+    ///
+    /// Restores a flag pushed with [`PushFlagClear`](Self::PushFlagClear).
+    ///
+    /// ```rust,ignore
+    /// flag = stack.pop_byte() != 0
+    /// ```
+    PopFlag = 91,
 
     /// Add data in memory to register F
     ///
     /// ```rust,ignore
     /// reg_f += transmute(memory[data]) // indexes 8 bytes
     /// ```
-    AddF(u16),
+    AddF(u16) = 42,
     /// Subtract data in memory from register F
     ///
     /// ```rust,ignore
     /// reg_f -= transmute(memory[data]) // indexes 8 bytes
     /// ```
-    SubF(u16),
+    SubF(u16) = 43,
     /// Multiply data in memory with register F to register F
     ///
     /// ```rust,ignore
     /// reg_f *= transmute(memory[data]) // indexes 8 bytes
     /// ```
-    MulF(u16),
+    MulF(u16) = 44,
     /// Divide register f with data in memory to register F
     ///
     /// ```rust,ignore
     /// reg_f /= transmute(memory[data]) // indexes 8 bytes
     /// ```
-    DivF(u16),
+    DivF(u16) = 45,
     /// data in memory to register F
     ///
     /// ```rust,ignore
     /// reg_f += transmute(memory[data]) // indexes 8 bytes
     /// ```
-    ModF(u16),
+    ModF(u16) = 46,
+    /// Square root of register F
+    ///
+    /// ```rust,ignore
+    /// reg_f = sqrt(reg_f)
+    /// if reg_f < 0 { // before the square root
+    ///     flag = true // reg_f is NaN
+    /// }
+    /// ```
+    SqrtF = 136,
+    /// Round register F down
+    ///
+    /// ```rust,ignore
+    /// reg_f = floor(reg_f)
+    /// ```
+    FloorF = 137,
+    /// Round register F up
+    ///
+    /// ```rust,ignore
+    /// reg_f = ceil(reg_f)
+    /// ```
+    CeilF = 138,
+    /// Round register F to the nearest integer (half away from zero)
+    ///
+    /// ```rust,ignore
+    /// reg_f = round(reg_f)
+    /// ```
+    RoundF = 139,
+    /// Truncate register F toward zero into register L
+    ///
+    /// Out of range values are clamped and NaN or infinite values
+    /// leave register L unchanged. Both set the flag.
+    ///
+    /// ```rust,ignore
+    /// if reg_f.is_finite() {
+    ///     reg_L = clamp(trunc(reg_f), 0, u16::MAX);
+    ///     if trunc(reg_f) < 0 || trunc(reg_f) > u16::MAX {
+    ///         flag = true;
+    ///     }
+    /// } else {
+    ///     flag = true;
+    /// }
+    /// ```
+    FtoL = 134,
+    /// Convert register L to a float in register F
+    ///
+    /// ```rust,ignore
+    /// reg_f = reg_L as f64
+    /// ```
+    LtoF = 135,
 
     // STACK
     /// Allocates x bytes on stack, if overflows, flag is set and it doesn't allocate
@@ -365,111 +678,190 @@ pub enum Instruction {
     /// flag = true
     /// }
     /// ```
-    StackAlloc(u16),
+    StackAlloc(u16) = 47,
     /// Deallocates x bytes on stack, if overflows, flag is set but it does clear the stack
     ///
     /// ```rust,ignore
     /// stack.dealloc(data)
     /// if overflow
     /// ```
-    StackDealloc(u16),
+    StackDealloc(u16) = 48,
+    /// Duplicate the byte on top of the stack, sets the flag if the stack is empty or full
+    ///
+    /// ```rust,ignore
+    /// stack.push_byte(stack.peek_byte())
+    /// ```
+    StackDup = 115,
+    /// Swap the top two bytes of the stack, sets the flag if there are less than 2
+    ///
+    /// ```rust,ignore
+    /// let (a, b) = (stack.pop(), stack.pop())
+    /// stack.push(a)
+    /// stack.push(b)
+    /// ```
+    StackSwap = 116,
+    /// Empty the stack
+    ///
+    /// ```rust,ignore
+    /// stack.clear()
+    /// ```
+    StackClear = 133,
 
     /// Push a value from memory to stack
     ///
     /// ```rust,ignore
     /// stack.push_byte(memory[data])
     /// ```
-    Push(u16),
+    Push(u16) = 49,
     /// Push an immediate value to stack
     ///
     /// ```rust,ignore
     /// stack.push_byte(data)
     /// ```
-    Pushi(u8),
+    Pushi(u8) = 50,
+    /// Push a 16-bit immediate value to stack (big endian, like [`PopL`](Self::PopL) pops it)
+    ///
+    /// ```rust,ignore
+    /// stack.push_bytes(data.to_be_bytes())
+    /// ```
+    PushiU16(u16) = 142,
     /// Pop a value from stack to memory, sets the flag if it can't
     ///
     /// ```rust,ignore
     /// memory[data] = stack.pop()
     /// ```
-    Pop(u16),
+    Pop(u16) = 51,
+    /// Pop a slice of the stack to memory, sets the flag and doesn't pop if there are
+    /// less than `data1` bytes on the stack or if the slice would go past the end of memory
+    ///
+    /// The bytes are written in the order they were pushed, so the top of the stack ends up at the highest address.
+    ///
+    /// ```rust,ignore
+    /// memory[data0..data0 + data1] = stack[stack.len() - data1..]
+    /// stack.dealloc(data1)
+    /// ```
+    PopMem(u16, u16) = 83,
 
     /// Pop to A
     ///
     /// ```rust,ignore
     /// reg_a = stack.pop_byte()
     /// ```
-    Popa,
+    Popa = 52,
     /// Push from A
     ///
     /// ```rust,ignore
     /// stack.push_byte(reg_a)
     /// ```
-    Pusha,
+    Pusha = 53,
 
     /// Pop to B
     ///
     /// ```rust,ignore
     /// reg_b = transmute( u16::from_bytes(stack.dealloc(2)) ) // transmute to i16
     /// ```
-    Popb,
+    Popb = 54,
     /// Push from B
     ///
     /// ```rust,ignore
     /// stack.push_bytes(reg_b.as_bytes())
     /// ```
-    Pushb,
+    Pushb = 55,
 
     /// Pop to L
     ///
     /// ```rust,ignore
     /// reg_L = u16::from_bytes(stack.dealloc(2))
     /// ```
-    PopL,
+    PopL = 56,
     /// Push from L
     ///
     /// ```rust,ignore
     /// stack.push_bytes(reg_L.as_bytes())
     /// ```
-    PushL,
+    PushL = 57,
+    /// Stack depth (in bytes) to L
+    ///
+    /// Sets the flag if the depth doesn't fit in register L.
+    ///
+    /// ```rust,ignore
+    /// reg_L = stack.used_space()
+    /// ```
+    SpToL = 103,
+    /// Execution pointer to L
+    ///
+    /// The execution pointer already points past this instruction when it's executed,
+    /// so this is the address of the next instruction (the address of `eptol` plus 1).
+    ///
+    /// ```rust,ignore
+    /// reg_L = reg_ep
+    /// ```
+    EpToL = 107,
 
     /// Pop to F
     ///
     /// ```rust,ignore
     /// reg_f = f64::from_bytes(stack.dealloc(8))
     /// ```
-    Popf,
+    Popf = 58,
     /// Push from F
     ///
     /// ```rust,ignore
     /// stack.push_bytes(reg_f.as_bytes())
     /// ```
-    Pushf,
+    Pushf = 59,
 
-    /// Pop to Ch
+    /// Pop to Ch, sets the flag and leaves Ch unchanged
+    /// if the popped value isn't a valid character
     ///
     /// ```rust,ignore
     /// reg_ch = char::from_bytes(stack.dealloc(4))
     /// ```
-    Popch,
+    Popch = 60,
     /// Push from Ch
     ///
     /// ```rust,ignore
     /// stack.push_bytes(reg_ch.as_bytes())
     /// ```
-    Pushch,
+    Pushch = 61,
 
-    /// Pop to Num
+    /// Pop to Num (4 bytes, big endian)
     ///
     /// ```rust,ignore
-    /// num_reg = i32::from_bytes(stack.dealloc(2))
+    /// num_reg = i32::from_be_bytes(stack.dealloc(4))
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// [`Pushnum`](Self::Pushnum) followed by `Popnum` leaves the number register unchanged:
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: ldinum -123_456_789;
+    ///         5: pushnum;
+    ///         6: ldinum 0;
+    ///         11: popnum;
+    ///         12: Ωtheendisnear;
+    ///         13: Ωskiptothechase;
+    ///     },
+    ///     0,
+    /// );
+    /// machine.run();
+    ///
+    /// assert_eq!(machine.num_reg, -123_456_789);
+    /// assert!(!machine.flag);
+    /// assert_eq!(machine.stack.used_space(), 0);
     /// ```
-    Popnum,
-    /// Push from Num
+    Popnum = 62,
+    /// Push from Num (4 bytes, big endian)
     ///
     /// ```rust,ignore
-    /// stack.push_bytes(num_reg.as_bytes())
+    /// stack.push_bytes(num_reg.to_be_bytes())
     /// ```
-    Pushnum,
+    Pushnum = 63,
 
     // Conditionals
     /// Pop to execution pointer
@@ -477,7 +869,7 @@ pub enum Instruction {
     /// ```rust,ignore
     /// reg_ep = stack.dealloc(2)
     /// ```
-    Popep,
+    Popep = 64,
     /// Pop to execution pointer if (B is) zero (aka equal)
     ///
     /// ```rust,ignore
@@ -485,7 +877,7 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Zpopep,
+    Zpopep = 65,
     /// Pop to execution pointer if positive (aka more than)
     ///
     /// ```rust,ignore
@@ -493,7 +885,7 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Ppopep,
+    Ppopep = 66,
     /// Pop to execution pointer if negative (aka less than)
     ///
     /// ```rust,ignore
@@ -501,7 +893,7 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Npopep,
+    Npopep = 67,
     /// Pop to execution pointer if flag (aka overflow/error)
     ///
     /// ```rust,ignore
@@ -509,7 +901,7 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Fpopep,
+    Fpopep = 68,
     /// Pop to execution pointer if register A is zero
     ///
     /// ```rust,ignore
@@ -517,7 +909,7 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Zapopep,
+    Zapopep = 69,
     /// Pop to execution pointer if debug mode is enabled
     ///
     /// ```rust,ignore
@@ -525,7 +917,93 @@ pub enum Instruction {
     ///     reg_ep = stack.dealloc(2)
     /// }
     /// ```
-    Dpopep,
+    Dpopep = 70,
+    /// Jump to the address in register L
+    ///
+    /// ```rust,ignore
+    /// reg_ep = reg_L
+    /// ```
+    JmpL = 82,
+    /// Jump relative to the execution pointer (the address after this instruction)
+    ///
+    /// ```rust,ignore
+    /// reg_ep = reg_ep.wrapping_add_signed(data)
+    /// ```
+    Jmp(i16) = 122,
+    /// Jump relative to the execution pointer if (B is) zero (aka equal), like [`Jmp`](Self::Jmp)
+    ///
+    /// ```rust,ignore
+    /// if reg_b == 0 {
+    ///     reg_ep = reg_ep.wrapping_add_signed(data)
+    /// }
+    /// ```
+    Jz(i16) = 123,
+    /// Jump relative to the execution pointer if (B is) not zero (aka not equal), like [`Jmp`](Self::Jmp)
+    ///
+    /// ```rust,ignore
+    /// if reg_b != 0 {
+    ///     reg_ep = reg_ep.wrapping_add_signed(data)
+    /// }
+    /// ```
+    Jnz(i16) = 124,
+    /// Jump relative to the execution pointer if flag (aka overflow/error), like [`Jmp`](Self::Jmp)
+    ///
+    /// ```rust,ignore
+    /// if flag == true {
+    ///     reg_ep = reg_ep.wrapping_add_signed(data)
+    /// }
+    /// ```
+    Jf(i16) = 125,
+    /// Clear flag and jump if it was set (aka overflow/error)
+    ///
+    /// ```rust,ignore
+    /// if flag == true {
+    ///     flag = false;
+    ///     reg_ep = data
+    /// }
+    /// ```
+    FjmpClear(u16) = 104,
+    /// Call a subroutine: push the execution pointer (the address after this instruction)
+    /// to stack and jump, sets the flag and doesn't jump if the stack is full
+    ///
+    /// ```rust,ignore
+    /// stack.push_u16(reg_ep)
+    /// reg_ep = data
+    /// ```
+    Call(u16) = 117,
+    /// Return from a subroutine: pop to execution pointer, like [`Popep`](Self::Popep)
+    ///
+    /// ```rust,ignore
+    /// reg_ep = stack.pop_u16()
+    /// ```
+    Ret = 118,
+    /// Halt the machine (with the exit code being the value of register A)
+    ///
+    /// Unlike [`ΩSkipToTheChase`](Self::ΩSkipToTheChase),
+    /// it doesn't need the feeling of impending doom.
+    ///
+    /// ```rust,ignore
+    /// halted = true
+    /// ```
+    Halt = 130,
+    /// Jump to the second operand if register A is equal to the first operand
+    ///
+    /// ```rust,ignore
+    /// if reg_a == data0 {
+    ///     reg_ep = data1
+    /// }
+    /// ```
+    BeqAi(u8, u16) = 85,
+    /// Jump to the third operand if the byte in memory at the first operand is equal to the second operand
+    ///
+    /// The instruction is 6 bytes long (the opcode, a 2 byte address, a byte and a 2 byte address).
+    ///
+    /// ```rust,ignore
+    /// if memory[data0] == data1 {
+    ///     reg_ep = data2
+    /// }
+    /// ```
+    JmemEq(u16, u8, u16) = 108,
 
     // IO
     /// Get a single character and put it in register Ch
@@ -542,13 +1020,37 @@ pub enum Instruction {
     ///
     /// disable_raw_mode();
     /// ```
-    GetChar,
-    /// Get a line and put it in register ß
+    GetChar = 71,
+    /// Get a line (without the line ending) and put it in register ß
+    ///
+    /// Sets the flag and leaves register ß empty if the line is longer than 255 bytes
+    /// or isn't valid UTF-8.
     ///
     /// ```rust,ignore
     /// get_line(reg_ß)
     /// ```
-    GetLine,
+    GetLine = 72,
+    /// Get a line and parse it as a decimal number into register num
+    ///
+    /// Sets the flag and leaves register num unchanged if the line is empty
+    /// or isn't a valid [`i32`] (surrounding whitespace is ignored).
+    ///
+    /// ```rust,ignore
+    /// num_reg = get_line().parse()
+    /// ```
+    ReadNum = 141,
+    /// Read a byte of input to register A (sets the flag at the end of input)
+    ///
+    /// ```rust,ignore
+    /// reg_a = read_byte()
+    /// ```
+    GetByte = 95,
+    /// Check if the input has ended, without consuming any of it
+    ///
+    /// ```rust,ignore
+    /// flag = peek_byte().is_none()
+    /// ```
+    AtEof = 102,
 
     /// Write a char from register Ch and flush
     ///
@@ -558,19 +1060,47 @@ pub enum Instruction {
     /// write_char(reg_ch)
     /// flush()
     /// ```
-    WriteChar,
+    WriteChar = 73,
+    /// Write register A as a raw byte
+    ///
+    /// ```rust,ignore
+    /// write_byte(reg_a)
+    /// ```
+    PutByte = 96,
+    /// Write register num as decimal text
+    ///
+    /// ```rust,ignore
+    /// write(num_reg.to_string())
+    /// ```
+    WriteNum = 140,
     /// Write a line from register ß
     ///
     /// ```rust,ignore
     /// write_line(reg_ß)
     /// ```
-    WriteLineß,
-    /// Write a line from memory (null terminated)
+    WriteLineß = 74,
+    /// Write a line from memory (null terminated, sets the flag and doesn't write if there is no null byte before the end of memory)
     ///
     /// ```rust,ignore
     /// write_line(c_string(memory[data]))
     /// ```
-    WriteLine(u16),
+    WriteLine(u16) = 75,
+    /// Write a line from memory if the flag is set (aka overflow/error), like [`WriteLine`](Self::WriteLine)
+    ///
+    /// The flag stays set.
+    ///
+    /// ```rust,ignore
+    /// if flag == true {
+    ///     write_line(c_string(memory[data]))
+    /// }
+    /// ```
+    WriteLineIfFlag(u16) = 114,
+    /// Write a region of the stack (start and length, clamped to the stack) as raw bytes
+    ///
+    /// ```rust,ignore
+    /// write(stack[data0..(data0 + data1)])
+    /// ```
+    WriteStack(u16, u16) = 92,
 
     // DEBUGGING:
     /// Toggles debug mode
@@ -578,36 +1108,567 @@ pub enum Instruction {
     /// ```rust,ignore
     /// debug_mode = !debug_mode
     /// ```
-    ToggleDebug,
+    ToggleDebug = 76,
     /// Debug print machine state
     ///
     /// ```rust,ignore
     /// println!("{:#?}", machine)
     /// ```
-    DebugMachineState,
+    DebugMachineState = 77,
     /// Debug print machine state compactly
     ///
     /// ```rust,ignore
     /// println!("{:?}", machine)
     /// ```
-    DebugMachineStateCompact,
+    DebugMachineStateCompact = 78,
     /// Debug print region of memory
     ///
+    /// Sets the flag and doesn't print if `data0` is after `data1`.
+    ///
     /// ```rust,ignore
     /// println!("{:?}", &memory[data0..data1])
     /// ```
-    DebugMemoryRegion(u16, u16),
+    DebugMemoryRegion(u16, u16) = 79,
     /// Debug print region of stack
     ///
+    /// Sets the flag and doesn't print if `data0` is after `data1`
+    /// or `data1` is past the top of the stack.
+    ///
     /// ```rust,ignore
     /// println!("{:?}", &stack[data0..data1])
     /// ```
-    DebugStackRegion(u16, u16),
+    DebugStackRegion(u16, u16) = 80,
     /// Print `reg_Ω.illusion_of_choice`.
     ///
     /// ```rust,ignore
     /// println!("{}", reg_Ω.illusion_of_choice)
-    ShowChoice,
+    ShowChoice = 81,
+}
+
+impl Instruction {
+    /// Returns how many bytes the instruction takes in memory:
+    /// 1 for the opcode plus the size of its operands.
+    ///
+    /// ```rust
+    /// # use esoteric_vm::instruction::Instruction;
+    /// assert_eq!(Instruction::Ldar(0).encoded_len(), 3);
+    /// assert_eq!(Instruction::Pushi(0).encoded_len(), 2);
+    /// assert_eq!(Instruction::Ldiř([0; 37]).encoded_len(), 38);
+    /// ```
+    #[must_use]
+    pub fn encoded_len(&self) -> u16 {
+        InstructionKind::from(self).encoded_len()
+    }
+
+    /// Returns whether the instruction reads input or writes output.
+    ///
+    /// ```rust
+    /// # use esoteric_vm::instruction::Instruction;
+    /// assert!(Instruction::WriteLine(0).is_io());
+    /// assert!(!Instruction::Nop.is_io());
+    /// ```
+    #[must_use]
+    pub const fn is_io(&self) -> bool {
+        matches!(
+            self,
+            Self::GetChar
+                | Self::GetLine
+                | Self::ReadNum
+                | Self::GetByte
+                | Self::AtEof
+                | Self::WriteChar
+                | Self::PutByte
+                | Self::WriteNum
+                | Self::WriteLineß
+                | Self::WriteLine(_)
+                | Self::WriteLineIfFlag(_)
+                | Self::WriteStack(_, _)
+                | Self::DebugMachineState
+                | Self::DebugMachineStateCompact
+                | Self::DebugMemoryRegion(_, _)
+                | Self::DebugStackRegion(_, _)
+                | Self::ShowChoice
+        )
+    }
+}
+
+/// The kind of an operand of an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operand {
+    /// An unsigned byte
+    U8,
+    /// A signed byte
+    I8,
+    /// A boolean (stored as a byte)
+    Bool,
+    /// An unsigned 16-bit integer
+    U16,
+    /// A signed 16-bit integer
+    I16,
+    /// A signed 32-bit integer
+    I32,
+    /// A float (stored as its bits)
+    F64,
+    /// An illusion of choice (stored as a byte)
+    Choice,
+    /// The 37 signed bytes of register ř
+    Array,
+}
+
+impl Operand {
+    /// Returns how many bytes the operand takes in memory.
+    pub(crate) const fn size(self) -> u16 {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool | Self::Choice => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::I32 => 4,
+            Self::F64 => 8,
+            Self::Array => 37,
+        }
+    }
+}
+
+/// The category of an instruction, following the sections of [`Instruction`].
+///
+/// Returned by [`InstructionKind::category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InstructionCategory {
+    /// Moving data between registers and memory
+    Register,
+    /// The registers of Ω
+    Ω,
+    /// Arithmetic, bitwise operations and the flag
+    Arithmetic,
+    /// Pushing to and popping from the stack
+    Stack,
+    /// Jumps and doing nothing
+    Control,
+    /// Input and output
+    Io,
+    /// Debugging
+    Debug,
+}
+
+impl InstructionKind {
+    /// Returns the category of this kind of instruction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::instruction::{InstructionCategory, InstructionKind};
+    /// assert_eq!(InstructionKind::AddBL.category(), InstructionCategory::Arithmetic);
+    /// assert_eq!(InstructionKind::Pushi.category(), InstructionCategory::Stack);
+    /// assert_eq!(InstructionKind::GetChar.category(), InstructionCategory::Io);
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub const fn category(self) -> InstructionCategory {
+        match self {
+            Self::Ldar
+            | Self::LdarL
+            | Self::StarL
+            | Self::MemFillA
+            | Self::MemCpy
+            | Self::MemSet
+            | Self::Shuffle
+            | Self::Sba
+            | Self::SwapAB
+            | Self::MovAB
+            | Self::MovBA
+            | Self::RotlA
+            | Self::RotrA
+            | Self::AToDec
+            | Self::Clř
+            | Self::Dumpř
+            | Self::Movař
+            | Self::Setř
+            | Self::Setiř
+            | Self::Ldř
+            | Self::Ldiř
+            | Self::Clß
+            | Self::Dumpß
+            | Self::Writeß
+            | Self::Movaß
+            | Self::Setß
+            | Self::Setiß
+            | Self::Ldß
+            | Self::Pushß
+            | Self::Popß
+            | Self::Lenßa
+            | Self::Lenßl
+            | Self::Jßeq
+            | Self::Ldidp
+            | Self::CheckDp
+            | Self::Ldinum
+            | Self::Ldif => InstructionCategory::Register,
+
+            Self::ΩChoiceSet
+            | Self::ΩChoiceGetA
+            | Self::ΩGainAPolymorphicDesires
+            | Self::ΩLoseAPolymorphicDesires
+            | Self::ΩPushPolymorphicDesires
+            | Self::ΩTheEndIsNear
+            | Self::ΩSkipToTheChase
+            | Self::ΩSetSentience
+            | Self::ΩSetPaperclipProduction => InstructionCategory::Ω,
+
+            Self::AddBL
+            | Self::SubBL
+            | Self::AddiL
+            | Self::SubiL
+            | Self::IncL
+            | Self::DecL
+            | Self::MulBL
+            | Self::DivBL
+            | Self::ModBL
+            | Self::NotL
+            | Self::AndBL
+            | Self::OrBL
+            | Self::XorBL
+            | Self::PopcntL
+            | Self::CtzL
+            | Self::ClzL
+            | Self::CmpLB
+            | Self::ClampL
+            | Self::CmpEqLB
+            | Self::CmpLtLB
+            | Self::CmpGtLB
+            | Self::TgFlag
+            | Self::ClFlag
+            | Self::PushFlagClear
+            | Self::PopFlag
+            | Self::AddF
+            | Self::SubF
+            | Self::MulF
+            | Self::DivF
+            | Self::ModF
+            | Self::SqrtF
+            | Self::FloorF
+            | Self::CeilF
+            | Self::RoundF
+            | Self::FtoL
+            | Self::LtoF => InstructionCategory::Arithmetic,
+
+            Self::StackAlloc
+            | Self::StackDealloc
+            | Self::StackDup
+            | Self::StackSwap
+            | Self::StackClear
+            | Self::Push
+            | Self::Pushi
+            | Self::PushiU16
+            | Self::Pop
+            | Self::PopMem
+            | Self::Popa
+            | Self::Pusha
+            | Self::Popb
+            | Self::Pushb
+            | Self::PopL
+            | Self::PushL
+            | Self::SpToL
+            | Self::EpToL
+            | Self::Popf
+            | Self::Pushf
+            | Self::Popch
+            | Self::Pushch
+            | Self::Popnum
+            | Self::Pushnum => InstructionCategory::Stack,
+
+            Self::Nop
+            | Self::Popep
+            | Self::Zpopep
+            | Self::Ppopep
+            | Self::Npopep
+            | Self::Fpopep
+            | Self::Zapopep
+            | Self::Dpopep
+            | Self::JmpL
+            | Self::Jmp
+            | Self::Jz
+            | Self::Jnz
+            | Self::Jf
+            | Self::FjmpClear
+            | Self::Call
+            | Self::Ret
+            | Self::Halt
+            | Self::BeqAi
+            | Self::JmemEq => InstructionCategory::Control,
+
+            Self::GetChar
+            | Self::GetLine
+            | Self::ReadNum
+            | Self::GetByte
+            | Self::AtEof
+            | Self::WriteChar
+            | Self::PutByte
+            | Self::WriteNum
+            | Self::WriteLineß
+            | Self::WriteLine
+            | Self::WriteLineIfFlag
+            | Self::WriteStack => InstructionCategory::Io,
+
+            Self::ToggleDebug
+            | Self::DebugMachineState
+            | Self::DebugMachineStateCompact
+            | Self::DebugMemoryRegion
+            | Self::DebugStackRegion
+            | Self::ShowChoice => InstructionCategory::Debug,
+        }
+    }
+
+    /// Returns the operands of this kind of instruction, in order.
+    #[allow(clippy::too_many_lines, clippy::match_same_arms)]
+    pub(crate) const fn operands(self) -> &'static [Operand] {
+        #[allow(clippy::enum_glob_use)]
+        use Operand::*;
+
+        match self {
+            Self::Nop => &[],
+
+            Self::Ldar => &[U16],
+            Self::LdarL => &[],
+            Self::StarL => &[],
+            Self::MemFillA => &[U16, U16],
+            Self::MemCpy => &[U16, U16, U16],
+            Self::MemSet => &[U16, U8, U16],
+            Self::Shuffle => &[U16, U16],
+            Self::Sba => &[],
+            Self::SwapAB => &[],
+            Self::MovAB => &[],
+            Self::MovBA => &[],
+            Self::RotlA => &[U8],
+            Self::RotrA => &[U8],
+            Self::AToDec => &[U16],
+
+            Self::Clř => &[],
+            Self::Dumpř => &[U16],
+            Self::Movař => &[U8],
+            Self::Setř => &[U8, U16],
+            Self::Setiř => &[U8, I8],
+            Self::Ldř => &[U16],
+            Self::Ldiř => &[Array],
+
+            Self::Clß => &[],
+            Self::Dumpß => &[U16],
+            Self::Writeß => &[U16, U8],
+            Self::Movaß => &[U8],
+            Self::Setß => &[U16, U8],
+            Self::Setiß => &[U8, U8],
+            Self::Ldß => &[U16],
+            Self::Pushß => &[],
+            Self::Popß => &[],
+            Self::Lenßa => &[],
+            Self::Lenßl => &[],
+            Self::Jßeq => &[U16, U16],
+
+            Self::Ldidp => &[U16],
+            Self::CheckDp => &[],
+            Self::Ldinum => &[I32],
+            Self::Ldif => &[F64],
+
+            Self::ΩChoiceSet => &[Choice],
+            Self::ΩChoiceGetA => &[],
+
+            Self::ΩGainAPolymorphicDesires => &[],
+            Self::ΩLoseAPolymorphicDesires => &[],
+            Self::ΩPushPolymorphicDesires => &[],
+
+            Self::ΩTheEndIsNear => &[],
+            Self::ΩSkipToTheChase => &[],
+
+            Self::ΩSetSentience => &[Bool],
+
+            Self::ΩSetPaperclipProduction => &[Bool],
+
+            Self::AddBL => &[],
+            Self::SubBL => &[],
+            Self::AddiL => &[I16],
+            Self::SubiL => &[I16],
+            Self::IncL => &[],
+            Self::DecL => &[],
+            Self::MulBL => &[],
+            Self::DivBL => &[],
+            Self::ModBL => &[],
+
+            Self::NotL => &[],
+
+            Self::AndBL => &[],
+            Self::OrBL => &[],
+            Self::XorBL => &[],
+            Self::PopcntL => &[],
+            Self::CtzL => &[],
+            Self::ClzL => &[],
+
+            Self::CmpLB => &[],
+            Self::ClampL => &[],
+            Self::CmpEqLB => &[],
+            Self::CmpLtLB => &[],
+            Self::CmpGtLB => &[],
+
+            Self::TgFlag => &[],
+            Self::ClFlag => &[],
+            Self::PushFlagClear => &[],
+            Self::PopFlag => &[],
+
+            Self::AddF => &[U16],
+            Self::SubF => &[U16],
+            Self::MulF => &[U16],
+            Self::DivF => &[U16],
+            Self::ModF => &[U16],
+            Self::SqrtF => &[],
+            Self::FloorF => &[],
+            Self::CeilF => &[],
+            Self::RoundF => &[],
+            Self::FtoL => &[],
+            Self::LtoF => &[],
+
+            Self::StackAlloc => &[U16],
+            Self::StackDealloc => &[U16],
+            Self::StackDup => &[],
+            Self::StackSwap => &[],
+            Self::StackClear => &[],
+
+            Self::Push => &[U16],
+            Self::Pushi => &[U8],
+            Self::PushiU16 => &[U16],
+            Self::Pop => &[U16],
+            Self::PopMem => &[U16, U16],
+
+            Self::Popa => &[],
+            Self::Pusha => &[],
+
+            Self::Popb => &[],
+            Self::Pushb => &[],
+
+            Self::PopL => &[],
+            Self::PushL => &[],
+            Self::SpToL => &[],
+            Self::EpToL => &[],
+
+            Self::Popf => &[],
+            Self::Pushf => &[],
+
+            Self::Popch => &[],
+            Self::Pushch => &[],
+
+            Self::Popnum => &[],
+            Self::Pushnum => &[],
+
+            Self::Popep => &[],
+            Self::Zpopep => &[],
+            Self::Ppopep => &[],
+            Self::Npopep => &[],
+            Self::Fpopep => &[],
+            Self::Zapopep => &[],
+            Self::Dpopep => &[],
+            Self::JmpL => &[],
+            Self::Jmp => &[I16],
+            Self::Jz => &[I16],
+            Self::Jnz => &[I16],
+            Self::Jf => &[I16],
+            Self::FjmpClear => &[U16],
+            Self::Call => &[U16],
+            Self::Ret => &[],
+            Self::Halt => &[],
+            Self::BeqAi => &[U8, U16],
+            Self::JmemEq => &[U16, U8, U16],
+
+            Self::GetChar => &[],
+            Self::GetLine => &[],
+            Self::ReadNum => &[],
+            Self::GetByte => &[],
+            Self::AtEof => &[],
+
+            Self::WriteChar => &[],
+            Self::PutByte => &[],
+            Self::WriteNum => &[],
+            Self::WriteLineß => &[],
+            Self::WriteLine => &[U16],
+            Self::WriteLineIfFlag => &[U16],
+            Self::WriteStack => &[U16, U16],
+
+            Self::ToggleDebug => &[],
+            Self::DebugMachineState => &[],
+            Self::DebugMachineStateCompact => &[],
+            Self::DebugMemoryRegion => &[U16, U16],
+            Self::DebugStackRegion => &[U16, U16],
+            Self::ShowChoice => &[],
+        }
+    }
+
+    /// Returns how many bytes an instruction of this kind takes in memory,
+    /// including the opcode.
+    #[must_use]
+    pub fn encoded_len(self) -> u16 {
+        self.operands()
+            .iter()
+            .fold(1, |len, operand| len.saturating_add(operand.size()))
+    }
+
+    /// Returns the assembly mnemonic of this kind of instruction.
+    ///
+    /// ```rust
+    /// # use esoteric_vm::instruction::InstructionKind;
+    /// assert_eq!(InstructionKind::Pushi.mnemonic(), "pushi");
+    /// assert_eq!(InstructionKind::StackDup.mnemonic(), "dup");
+    /// ```
+    #[must_use]
+    pub fn mnemonic(self) -> String {
+        RENAMED
+            .iter()
+            .find(|&&(renamed, _)| renamed == self)
+            .map_or_else(
+                || normalize_mnemonic(&format!("{self:?}")),
+                |&(_, name)| name.to_owned(),
+            )
+    }
+}
+
+/// Mnemonics that aren't the lowercased name of their instruction.
+const RENAMED: [(InstructionKind, &str); 3] = [
+    (InstructionKind::StackDup, "dup"),
+    (InstructionKind::StackSwap, "swap"),
+    (InstructionKind::PushiU16, "pushi16"),
+];
+
+/// Lowercases a mnemonic, except for Ω (which has both spellings).
+pub(crate) fn normalize_mnemonic(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| match c {
+            '\u{2126}' | '\u{3a9}' => vec!['\u{2126}'],
+            c => c.to_lowercase().collect(),
+        })
+        .collect()
+}
+
+/// Writes the instruction like it's written in assembly, e.g. `pushi 46`.
+///
+/// ```rust
+/// # use esoteric_vm::instruction::Instruction;
+/// assert_eq!(Instruction::Pushi(46).to_string(), "pushi 46");
+/// assert_eq!(Instruction::Setř(3, 1000).to_string(), "setř 3, 1000");
+/// assert_eq!(Instruction::Ldif(2.5_f64.to_bits()).to_string(), "ldif 2.5");
+/// ```
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&InstructionKind::from(self).mnemonic())?;
+
+        // the assembly takes the float itself
+        if let Self::Ldif(bits) = self {
+            return write!(f, " {:?}", f64::from_bits(*bits));
+        }
+
+        // the other operands are written in assembly like the fields in the `Debug` output
+        let debug = format!("{self:?}");
+        if let Some(operands) = debug
+            .split_once('(')
+            .and_then(|(_, operands)| operands.strip_suffix(')'))
+        {
+            write!(f, " {operands}")?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Data or an instruction.
@@ -619,6 +1680,48 @@ pub enum DataOrInstruction<'a> {
     ByteData(u8),
     /// A slice of byte data
     Data(&'a [u8]),
+    /// An amount of zero bytes
+    Zeroed(u16),
+    /// An amount of copies of a byte
+    Fill(u8, u16),
+    /// Zero bytes up to the next address that's a multiple of the value
+    /// (nothing if the address already is one or the value is 0)
+    Align(u16),
+    /// A byte string pushed to the stack by a [`Pushi`](Instruction::Pushi) per byte,
+    /// in order (so the last byte ends up on top of the stack)
+    PushString(&'a [u8]),
     /// A regular instruction
     Instruction(Instruction),
 }
+
+impl DataOrInstruction<'_> {
+    /// Returns how many bytes this takes in memory when it's loaded at `address`
+    /// (which only matters for [`Align`](Self::Align)).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::instruction::{DataOrInstruction, Instruction};
+    /// assert_eq!(DataOrInstruction::Instruction(Instruction::Pushi(1)).loaded_len(0), 2);
+    /// assert_eq!(DataOrInstruction::Data(b"hi").loaded_len(0), 2);
+    /// assert_eq!(DataOrInstruction::Align(4).loaded_len(5), 3);
+    /// ```
+    #[must_use]
+    pub fn loaded_len(&self, address: u16) -> u16 {
+        let len = |bytes: &[u8]| u16::try_from(bytes.len()).unwrap_or(u16::MAX);
+
+        match self {
+            Self::ByteData(_) => 1,
+            Self::Data(bytes) => len(bytes),
+            Self::Zeroed(amount) | Self::Fill(_, amount) => *amount,
+            Self::Align(alignment) => match address.checked_rem(*alignment) {
+                None | Some(0) => 0,
+                Some(rem) => alignment.wrapping_sub(rem),
+            },
+            Self::PushString(bytes) => {
+                len(bytes).saturating_mul(InstructionKind::Pushi.encoded_len())
+            }
+            Self::Instruction(instruction) => instruction.encoded_len(),
+        }
+    }
+}
@@ -47,6 +47,12 @@
 //! machine
 //! # }
 //! ```
+//!
+//! # Features
+//!
+//! - `serde`: implements `Serialize` and `Deserialize` for [`Machine`] (its state,
+//!   without the IO backend, the journal, the flag statistics and the trace),
+//!   so a machine can be saved and restored.
 
 #![warn(
     clippy::pedantic,
@@ -94,4 +100,85 @@ pub(crate) mod utils {
 }
 pub mod assembly;
 
+use std::{error::Error, fmt};
+
 pub use machine::Machine;
+pub use utils::constant_size_string::{ConstantSizeString, Overflow};
+pub use utils::primes;
+
+use machine::{stack::stackoverflow::StackOverflow, LoadError, RunError};
+
+/// Any error of the crate.
+///
+/// Every error type converts into it, so `?` works
+/// when mixing operations with different errors.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{machine::stack::Stack, VmError};
+/// fn fill(stack: &mut Stack) -> Result<(), VmError> {
+///     loop {
+///         stack.push_byte(0)?;
+///     }
+/// }
+///
+/// assert!(matches!(fill(&mut Stack::default()), Err(VmError::StackOverflow(_))));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmError {
+    /// The stack overflowed
+    StackOverflow(StackOverflow),
+    /// A string overflowed
+    Overflow(Overflow),
+    /// Running a machine failed
+    Run(RunError),
+    /// Loading data into a machine failed
+    Load(LoadError),
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StackOverflow(err) => fmt::Display::fmt(err, f),
+            Self::Overflow(err) => fmt::Display::fmt(err, f),
+            Self::Run(err) => fmt::Display::fmt(err, f),
+            Self::Load(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for VmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::StackOverflow(err) => Some(err),
+            Self::Overflow(err) => Some(err),
+            Self::Run(err) => Some(err),
+            Self::Load(err) => Some(err),
+        }
+    }
+}
+
+impl From<StackOverflow> for VmError {
+    fn from(err: StackOverflow) -> Self {
+        Self::StackOverflow(err)
+    }
+}
+
+impl From<Overflow> for VmError {
+    fn from(err: Overflow) -> Self {
+        Self::Overflow(err)
+    }
+}
+
+impl From<RunError> for VmError {
+    fn from(err: RunError) -> Self {
+        Self::Run(err)
+    }
+}
+
+impl From<LoadError> for VmError {
+    fn from(err: LoadError) -> Self {
+        Self::Load(err)
+    }
+}
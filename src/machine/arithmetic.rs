@@ -0,0 +1,65 @@
+//! How arithmetic overflow is handled.
+//!
+//! More info at [`ArithmeticMode`].
+
+use super::{halt::HaltReason, Machine};
+use crate::utils::non_invalidatable::transmute as safe_transmute;
+
+/// The exit code (register A) of a machine halted by [`ArithmeticMode::Trap`].
+///
+/// It's the exit code of a process killed by `SIGFPE`.
+pub const TRAP_EXIT_CODE: u8 = 136;
+
+/// How `addbl`, `subbl`, `addil`, `subil`, `mulbl`, `divbl`, `modbl`, `incl` and `decl` handle overflow
+/// (and division by zero, which leaves register L unchanged in both modes).
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{esoteric_assembly, machine::arithmetic::{ArithmeticMode, TRAP_EXIT_CODE}, Machine};
+/// let mut machine = Machine::default();
+/// machine.load(
+///     &esoteric_assembly! {
+///         0: subbl;
+///         1: Ωtheendisnear;
+///         2: Ωskiptothechase;
+///     },
+///     0,
+/// );
+/// machine.reg_b = 1;
+/// machine.arithmetic_mode = ArithmeticMode::Trap;
+///
+/// assert_eq!(machine.run(), TRAP_EXIT_CODE);
+/// assert_eq!(machine.reg_ep, 1);
+/// assert_eq!(machine.reg_L, 0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArithmeticMode {
+    /// Wrap around and set the flag on overflow (clearing it otherwise).
+    #[default]
+    Flag,
+    /// Halt the machine with [`TRAP_EXIT_CODE`] on overflow,
+    /// leaving register L unchanged.
+    Trap,
+}
+
+impl Machine {
+    /// Applies `op` to register L and register B (transmuted to u16)
+    /// and handles overflow according to the [`arithmetic_mode`](Self::arithmetic_mode).
+    pub(super) fn arithmetic(&mut self, op: impl FnOnce(u16, u16) -> (u16, bool)) {
+        let (result, overflow) = op(self.reg_L, safe_transmute(self.reg_b));
+
+        if overflow && self.arithmetic_mode == ArithmeticMode::Trap {
+            self.trap();
+        } else {
+            (self.reg_L, self.flag) = (result, overflow);
+        }
+    }
+
+    /// Halts the machine with [`TRAP_EXIT_CODE`].
+    pub(super) const fn trap(&mut self) {
+        self.reg_a = TRAP_EXIT_CODE;
+        self.halt(HaltReason::Trap);
+    }
+}
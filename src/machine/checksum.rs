@@ -0,0 +1,110 @@
+//! Checksums of machine states.
+//!
+//! More info at [`Machine::memory_checksum`] and [`Machine::state_checksum`].
+
+use super::{decoder::choice_to_byte, Machine};
+
+/// A 64-bit FNV-1a hasher.
+struct Fnv(u64);
+
+impl Fnv {
+    /// The FNV-1a 64-bit offset basis.
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    /// The FNV-1a 64-bit prime.
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    /// Makes a new hasher.
+    const fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    /// Hashes bytes.
+    fn write(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+        self
+    }
+}
+
+impl Machine {
+    /// Returns a checksum (64-bit FNV-1a) of the memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// let checksum = machine.memory_checksum();
+    ///
+    /// machine.memory[1000] = 1;
+    /// assert_ne!(machine.memory_checksum(), checksum);
+    ///
+    /// machine.memory[1000] = 0;
+    /// assert_eq!(machine.memory_checksum(), checksum);
+    /// ```
+    #[must_use]
+    pub fn memory_checksum(&self) -> u64 {
+        Fnv::new().write(self.memory.as_slice()).0
+    }
+
+    /// Returns a checksum (64-bit FNV-1a) of the registers,
+    /// the stack and the memory.
+    ///
+    /// Two machines in the same state have the same checksum,
+    /// so it can stand in for comparing whole machines.
+    /// The IO, journal, statistics and trace settings aren't part of the state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{instruction::Instruction, Machine};
+    /// let mut machine = Machine::default();
+    /// let checksum = machine.state_checksum();
+    ///
+    /// machine.execute_instruction(Instruction::Pushi(1));
+    /// assert_ne!(machine.state_checksum(), checksum);
+    ///
+    /// machine.execute_instruction(Instruction::Popa);
+    /// assert_ne!(machine.state_checksum(), checksum);
+    ///
+    /// machine.reg_a = 0;
+    /// assert_eq!(machine.state_checksum(), checksum);
+    /// ```
+    #[must_use]
+    pub fn state_checksum(&self) -> u64 {
+        let mut fnv = Fnv::new();
+
+        fnv.write(&[self.reg_a])
+            .write(&self.reg_b.to_be_bytes())
+            .write(&self.reg_L.to_be_bytes())
+            .write(&self.reg_f.to_bits().to_be_bytes())
+            .write(&u32::from(self.reg_ch).to_be_bytes())
+            .write(&self.reg_ř.map(|v| v.to_be_bytes()[0]))
+            .write(&self.reg_ß.len().to_be_bytes())
+            .write(self.reg_ß.as_bytes());
+
+        let omega = &self.reg_Ω;
+        fnv.write(&[choice_to_byte(omega.illusion_of_choice)])
+            .write(&omega.polymorphic_desires.to_be_bytes())
+            .write(&[
+                u8::from(omega.feeling_of_impending_doom),
+                u8::from(omega.is_sentient),
+                u8::from(omega.should_make_infinite_paperclips),
+            ]);
+
+        fnv.write(&self.num_reg.to_be_bytes())
+            .write(&self.reg_ep.to_be_bytes())
+            .write(&self.reg_dp.to_be_bytes())
+            .write(&[
+                u8::from(self.flag),
+                u8::from(self.debug_mode),
+                u8::from(self.halted),
+            ])
+            .write(&self.rng_state.to_be_bytes())
+            .write(&self.stack.vec.len().to_be_bytes())
+            .write(&self.stack.vec)
+            .write(self.memory.as_slice())
+            .0
+    }
+}
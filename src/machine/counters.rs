@@ -0,0 +1,44 @@
+//! Counters of executed instructions for profiling.
+//!
+//! More info at [`Machine::instructions_executed`] and [`Machine::opcode_counts`].
+
+use super::Machine;
+use crate::instruction::{Instruction, InstructionKind};
+
+impl Machine {
+    /// Counts a fetched instruction in [`opcode_counts`](Self::opcode_counts).
+    pub(super) fn count_opcode(&mut self, instruction: Instruction) {
+        #[allow(clippy::indexing_slicing)]
+        let count = &mut self.opcode_counts[InstructionKind::from(instruction) as usize];
+        *count = count.saturating_add(1);
+    }
+
+    /// Returns how many instructions of `kind` were fetched.
+    #[must_use]
+    pub fn opcode_count(&self, kind: InstructionKind) -> u64 {
+        self.opcode_counts
+            .get(kind as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets [`instructions_executed`](Self::instructions_executed)
+    /// and [`opcode_counts`](Self::opcode_counts) to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{instruction::{Instruction, InstructionKind}, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.execute_instruction(Instruction::Nop);
+    /// assert_eq!(machine.instructions_executed, 1);
+    ///
+    /// machine.reset_counters();
+    /// assert_eq!(machine.instructions_executed, 0);
+    /// assert_eq!(machine.opcode_count(InstructionKind::Nop), 0);
+    /// ```
+    pub fn reset_counters(&mut self) {
+        self.instructions_executed = 0;
+        *self.opcode_counts = [0; 256];
+    }
+}
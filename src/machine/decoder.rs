@@ -0,0 +1,451 @@
+//! Decoding instructions from memory.
+//!
+//! More info at [`Decoder`] and [`Machine::scan_instructions`].
+
+use std::{error::Error, fmt, mem::transmute};
+
+use super::Machine;
+use crate::{
+    instruction::{Instruction, InstructionKind},
+    utils::non_invalidatable::transmute as safe_transmute,
+};
+
+/// Decodes instructions from memory without executing them.
+///
+/// This is what [`Machine::fetch_instruction`] uses,
+/// but it only borrows the memory, so it can't change a machine.
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    /// The memory to decode from
+    memory: &'a [u8],
+    /// The address of the next byte to read
+    position: u16,
+    /// Whether a read went past the end of the memory
+    out_of_bounds: bool,
+}
+
+impl<'a> Decoder<'a> {
+    /// Makes a new [`Decoder`] that starts reading at `position`.
+    #[must_use]
+    pub const fn new(memory: &'a [u8], position: u16) -> Self {
+        Self {
+            memory,
+            position,
+            out_of_bounds: false,
+        }
+    }
+
+    /// Returns the address of the next byte to read.
+    #[must_use]
+    pub const fn position(&self) -> u16 {
+        self.position
+    }
+
+    /// Reads a byte and increments the position by 1 (wrapping around).
+    fn fetch_byte(&mut self) -> u8 {
+        let byte = self.memory.get(self.position as usize).copied();
+        self.position = self.position.wrapping_add(1);
+
+        byte.unwrap_or_else(|| {
+            self.out_of_bounds = true;
+            0
+        })
+    }
+    /// Reads 2 bytes as a big endian integer.
+    fn fetch_2_bytes(&mut self) -> u16 {
+        u16::from_be_bytes([self.fetch_byte(), self.fetch_byte()])
+    }
+    /// Reads 4 bytes as a big endian integer.
+    fn fetch_4_bytes(&mut self) -> u32 {
+        u32::from_be_bytes([0; 4].map(|_| self.fetch_byte()))
+    }
+    /// Reads 8 bytes as a big endian integer.
+    fn fetch_8_bytes(&mut self) -> u64 {
+        u64::from_be_bytes([0; 8].map(|_| self.fetch_byte()))
+    }
+
+    /// Decodes the instruction at the current position
+    /// and moves past it.
+    ///
+    /// Returns `None` if the opcode or an operand is invalid,
+    /// or if the instruction goes past the end of the memory.
+    pub fn decode(&mut self) -> Option<Instruction> {
+        self.out_of_bounds = false;
+        let instruction = self.decode_unchecked();
+
+        if self.out_of_bounds {
+            None
+        } else {
+            instruction
+        }
+    }
+
+    /// Decodes the instruction at the current position,
+    /// reading zeroes past the end of the memory.
+    #[allow(clippy::too_many_lines)]
+    fn decode_unchecked(&mut self) -> Option<Instruction> {
+        use {Instruction as I, InstructionKind as IK};
+
+        Some(match InstructionKind::from_repr(self.fetch_byte())? {
+            IK::Nop => I::Nop,
+
+            IK::Ldar => I::Ldar(self.fetch_2_bytes()),
+            IK::LdarL => I::LdarL,
+            IK::StarL => I::StarL,
+            IK::MemFillA => I::MemFillA(self.fetch_2_bytes(), self.fetch_2_bytes()),
+            IK::MemCpy => I::MemCpy(
+                self.fetch_2_bytes(),
+                self.fetch_2_bytes(),
+                self.fetch_2_bytes(),
+            ),
+            IK::MemSet => I::MemSet(
+                self.fetch_2_bytes(),
+                self.fetch_byte(),
+                self.fetch_2_bytes(),
+            ),
+            IK::Shuffle => I::Shuffle(self.fetch_2_bytes(), self.fetch_2_bytes()),
+            IK::Sba => I::Sba,
+            IK::SwapAB => I::SwapAB,
+            IK::MovAB => I::MovAB,
+            IK::MovBA => I::MovBA,
+            IK::RotlA => I::RotlA(self.fetch_byte()),
+            IK::RotrA => I::RotrA(self.fetch_byte()),
+            IK::AToDec => I::AToDec(self.fetch_2_bytes()),
+
+            IK::Clř => I::Clř,
+            IK::Dumpř => I::Dumpř(self.fetch_2_bytes()),
+            IK::Movař => I::Movař(self.fetch_byte()),
+            IK::Setř => I::Setř(self.fetch_byte(), self.fetch_2_bytes()),
+            IK::Setiř => I::Setiř(
+                self.fetch_byte(),
+                safe_transmute::<u8, i8, 1>(self.fetch_byte()),
+            ),
+            IK::Ldř => I::Ldř(self.fetch_2_bytes()),
+            IK::Ldiř => {
+                let mut array = [0; 37];
+
+                for item in &mut array {
+                    *item = safe_transmute::<u8, i8, 1>(self.fetch_byte());
+                }
+
+                I::Ldiř(array)
+            }
+
+            IK::Clß => I::Clß,
+            IK::Dumpß => I::Dumpß(self.fetch_2_bytes()),
+            IK::Writeß => I::Writeß(self.fetch_2_bytes(), self.fetch_byte()),
+            IK::Movaß => I::Movaß(self.fetch_byte()),
+            IK::Setß => I::Setß(self.fetch_2_bytes(), self.fetch_byte()),
+            IK::Setiß => I::Setiß(self.fetch_byte(), self.fetch_byte()),
+            IK::Ldß => I::Ldß(self.fetch_2_bytes()),
+            IK::Pushß => I::Pushß,
+            IK::Popß => I::Popß,
+            IK::Lenßa => I::Lenßa,
+            IK::Lenßl => I::Lenßl,
+            IK::Jßeq => I::Jßeq(self.fetch_2_bytes(), self.fetch_2_bytes()),
+            IK::Ldidp => I::Ldidp(self.fetch_2_bytes()),
+            IK::CheckDp => I::CheckDp,
+            IK::Ldinum => I::Ldinum(safe_transmute::<u32, i32, 4>(self.fetch_4_bytes())),
+            IK::Ldif => I::Ldif(self.fetch_8_bytes()),
+
+            IK::ΩChoiceSet => I::ΩChoiceSet(choice_from_byte(self.fetch_byte())?),
+            IK::ΩChoiceGetA => I::ΩChoiceGetA,
+
+            IK::ΩGainAPolymorphicDesires => I::ΩGainAPolymorphicDesires,
+            IK::ΩLoseAPolymorphicDesires => I::ΩLoseAPolymorphicDesires,
+            IK::ΩPushPolymorphicDesires => I::ΩPushPolymorphicDesires,
+
+            IK::ΩTheEndIsNear => I::ΩTheEndIsNear,
+            IK::ΩSkipToTheChase => I::ΩSkipToTheChase,
+
+            IK::ΩSetSentience => I::ΩSetSentience(self.fetch_byte() != 0),
+            IK::ΩSetPaperclipProduction => I::ΩSetPaperclipProduction(self.fetch_byte() != 0),
+
+            IK::AddBL => I::AddBL,
+            IK::SubBL => I::SubBL,
+            IK::AddiL => I::AddiL(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::SubiL => I::SubiL(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::IncL => I::IncL,
+            IK::DecL => I::DecL,
+            IK::MulBL => I::MulBL,
+            IK::DivBL => I::DivBL,
+            IK::ModBL => I::ModBL,
+
+            IK::NotL => I::NotL,
+            IK::AndBL => I::AndBL,
+            IK::OrBL => I::OrBL,
+            IK::XorBL => I::XorBL,
+            IK::PopcntL => I::PopcntL,
+            IK::CtzL => I::CtzL,
+            IK::ClzL => I::ClzL,
+
+            IK::CmpLB => I::CmpLB,
+            IK::ClampL => I::ClampL,
+            IK::CmpEqLB => I::CmpEqLB,
+            IK::CmpLtLB => I::CmpLtLB,
+            IK::CmpGtLB => I::CmpGtLB,
+
+            IK::TgFlag => I::TgFlag,
+            IK::ClFlag => I::ClFlag,
+            IK::PushFlagClear => I::PushFlagClear,
+            IK::PopFlag => I::PopFlag,
+
+            IK::AddF => I::AddF(self.fetch_2_bytes()),
+            IK::SubF => I::SubF(self.fetch_2_bytes()),
+            IK::MulF => I::MulF(self.fetch_2_bytes()),
+            IK::DivF => I::DivF(self.fetch_2_bytes()),
+            IK::ModF => I::ModF(self.fetch_2_bytes()),
+            IK::SqrtF => I::SqrtF,
+            IK::FloorF => I::FloorF,
+            IK::CeilF => I::CeilF,
+            IK::RoundF => I::RoundF,
+            IK::FtoL => I::FtoL,
+            IK::LtoF => I::LtoF,
+
+            IK::StackAlloc => I::StackAlloc(self.fetch_2_bytes()),
+            IK::StackDealloc => I::StackDealloc(self.fetch_2_bytes()),
+            IK::StackDup => I::StackDup,
+            IK::StackSwap => I::StackSwap,
+            IK::StackClear => I::StackClear,
+
+            IK::Push => I::Push(self.fetch_2_bytes()),
+            IK::Pushi => I::Pushi(self.fetch_byte()),
+            IK::PushiU16 => I::PushiU16(self.fetch_2_bytes()),
+            IK::Pop => I::Pop(self.fetch_2_bytes()),
+            IK::PopMem => I::PopMem(self.fetch_2_bytes(), self.fetch_2_bytes()),
+
+            IK::Popa => I::Popa,
+            IK::Pusha => I::Pusha,
+
+            IK::Popb => I::Popb,
+            IK::Pushb => I::Pushb,
+
+            IK::PopL => I::PopL,
+            IK::PushL => I::PushL,
+            IK::SpToL => I::SpToL,
+            IK::EpToL => I::EpToL,
+
+            IK::Popf => I::Popf,
+            IK::Pushf => I::Pushf,
+
+            IK::Popch => I::Popch,
+            IK::Pushch => I::Pushch,
+
+            IK::Popnum => I::Popnum,
+            IK::Pushnum => I::Pushnum,
+
+            IK::Popep => I::Popep,
+            IK::Zpopep => I::Zpopep,
+            IK::Ppopep => I::Ppopep,
+            IK::Npopep => I::Npopep,
+            IK::Fpopep => I::Fpopep,
+            IK::Zapopep => I::Zapopep,
+            IK::Dpopep => I::Dpopep,
+            IK::JmpL => I::JmpL,
+            IK::Jmp => I::Jmp(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::Jz => I::Jz(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::Jnz => I::Jnz(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::Jf => I::Jf(safe_transmute::<u16, i16, 2>(self.fetch_2_bytes())),
+            IK::FjmpClear => I::FjmpClear(self.fetch_2_bytes()),
+            IK::Call => I::Call(self.fetch_2_bytes()),
+            IK::Ret => I::Ret,
+            IK::Halt => I::Halt,
+            IK::BeqAi => I::BeqAi(self.fetch_byte(), self.fetch_2_bytes()),
+            IK::JmemEq => I::JmemEq(
+                self.fetch_2_bytes(),
+                self.fetch_byte(),
+                self.fetch_2_bytes(),
+            ),
+
+            IK::GetChar => I::GetChar,
+            IK::GetLine => I::GetLine,
+            IK::ReadNum => I::ReadNum,
+            IK::GetByte => I::GetByte,
+            IK::AtEof => I::AtEof,
+
+            IK::WriteChar => I::WriteChar,
+            IK::PutByte => I::PutByte,
+            IK::WriteNum => I::WriteNum,
+            IK::WriteLineß => I::WriteLineß,
+            IK::WriteLine => I::WriteLine(self.fetch_2_bytes()),
+            IK::WriteLineIfFlag => I::WriteLineIfFlag(self.fetch_2_bytes()),
+            IK::WriteStack => I::WriteStack(self.fetch_2_bytes(), self.fetch_2_bytes()),
+
+            IK::ToggleDebug => I::ToggleDebug,
+            IK::DebugMachineState => I::DebugMachineState,
+            IK::DebugMachineStateCompact => I::DebugMachineStateCompact,
+            IK::DebugMemoryRegion => {
+                I::DebugMemoryRegion(self.fetch_2_bytes(), self.fetch_2_bytes())
+            }
+            IK::DebugStackRegion => I::DebugStackRegion(self.fetch_2_bytes(), self.fetch_2_bytes()),
+            IK::ShowChoice => I::ShowChoice,
+        })
+    }
+}
+
+/// Turns a byte into an illusion of choice, if it's a valid one.
+#[allow(clippy::option_option)]
+pub(crate) fn choice_from_byte(byte: u8) -> Option<Option<Option<Option<Option<()>>>>> {
+    [
+        None,
+        Some(None),
+        Some(Some(None)),
+        Some(Some(Some(None))),
+        Some(Some(Some(Some(())))),
+    ]
+    .into_iter()
+    // SAFETY: every candidate is a valid value, which is a single initialized byte
+    .find(|choice| unsafe { transmute::<Option<Option<Option<Option<()>>>>, u8>(*choice) } == byte)
+}
+
+/// Turns an illusion of choice into its byte representation.
+#[allow(clippy::option_option)]
+pub(crate) const fn choice_to_byte(choice: Option<Option<Option<Option<()>>>>) -> u8 {
+    // SAFETY: an illusion of choice is a single initialized byte
+    unsafe { transmute::<Option<Option<Option<Option<()>>>>, u8>(choice) }
+}
+
+/// An error from [`Machine::scan_instructions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanError {
+    /// The byte at `at` isn't a valid opcode.
+    InvalidOpcode {
+        /// The invalid opcode
+        opcode: u8,
+        /// The address of the opcode
+        at: u16,
+    },
+    /// The instruction at `at` has an invalid operand.
+    InvalidOperand {
+        /// The address of the instruction
+        at: u16,
+    },
+    /// The instruction at `at` goes past the end of the scanned region.
+    Truncated {
+        /// The address of the instruction
+        at: u16,
+    },
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOpcode { opcode, at } => write!(f, "invalid opcode {opcode} at {at}"),
+            Self::InvalidOperand { at } => write!(f, "invalid operand for the instruction at {at}"),
+            Self::Truncated { at } => write!(f, "the instruction at {at} is truncated"),
+        }
+    }
+}
+
+impl Error for ScanError {}
+
+impl Machine {
+    /// Decodes every instruction in the memory region `start..end`
+    /// without executing anything, returning them with their addresses.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ScanError`] found in the region.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, instruction::Instruction, machine::decoder::ScanError, Machine};
+    /// let mut machine = Machine::default();
+    /// let end = machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 7;
+    ///         2: nop;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(
+    ///     machine.scan_instructions(0, end),
+    ///     Ok(vec![(0, Instruction::Pushi(7)), (2, Instruction::Nop)])
+    /// );
+    ///
+    /// machine.memory[usize::from(end)] = 255;
+    /// assert_eq!(
+    ///     machine.scan_instructions(0, end + 1),
+    ///     Err(ScanError::InvalidOpcode { opcode: 255, at: end })
+    /// );
+    /// ```
+    pub fn scan_instructions(
+        &self,
+        start: u16,
+        end: u16,
+    ) -> Result<Vec<(u16, Instruction)>, ScanError> {
+        let mut instructions = Vec::new();
+        let mut decoder = Decoder::new(self.memory.as_slice(), start);
+
+        while decoder.position() < end {
+            let at = decoder.position();
+            let instruction = decoder.decode();
+            let next = decoder.position();
+
+            if next > end || next <= at || decoder.out_of_bounds {
+                return Err(ScanError::Truncated { at });
+            }
+
+            let Some(instruction) = instruction else {
+                let opcode = self.memory.get(at as usize).copied().unwrap_or_default();
+
+                return Err(if InstructionKind::from_repr(opcode).is_none() {
+                    ScanError::InvalidOpcode { opcode, at }
+                } else {
+                    ScanError::InvalidOperand { at }
+                });
+            };
+
+            instructions.push((at, instruction));
+        }
+
+        Ok(instructions)
+    }
+
+    /// Decodes up to `count` instructions from memory starting at `start`
+    /// without executing anything (or changing [`reg_ep`](Self::reg_ep)),
+    /// returning them with their addresses.
+    ///
+    /// Stops early at the first invalid instruction or at the end of memory,
+    /// returning what was decoded before it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, instruction::Instruction, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 7;
+    ///         2: nop;
+    ///         3: byte 255;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(machine.disassemble(0, 1), [(0, Instruction::Pushi(7))]);
+    /// assert_eq!(
+    ///     machine.disassemble(0, 10),
+    ///     [(0, Instruction::Pushi(7)), (2, Instruction::Nop)]
+    /// );
+    /// assert_eq!(machine.reg_ep, 0);
+    /// ```
+    #[must_use]
+    pub fn disassemble(&self, start: u16, count: usize) -> Vec<(u16, Instruction)> {
+        let mut instructions = Vec::new();
+        let mut decoder = Decoder::new(self.memory.as_slice(), start);
+
+        while instructions.len() < count {
+            let at = decoder.position();
+            let Some(instruction) = decoder.decode() else {
+                break;
+            };
+
+            instructions.push((at, instruction));
+        }
+
+        instructions
+    }
+}
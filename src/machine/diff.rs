@@ -0,0 +1,240 @@
+//! Differences between machine states.
+//!
+//! More info at [`Machine::diff`].
+
+use super::{halt::HaltReason, omega::Ω, Machine};
+use crate::utils::constant_size_string::ConstantSizeString;
+
+/// A single difference between two machine states.
+///
+/// The `old` value always belongs to the machine [`Machine::diff`]
+/// was called on and the `new` value belongs to the other machine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateChange {
+    /// Register A differs.
+    RegA {
+        /// The old value
+        old: u8,
+        /// The new value
+        new: u8,
+    },
+    /// Register B differs.
+    RegB {
+        /// The old value
+        old: i16,
+        /// The new value
+        new: i16,
+    },
+    /// Register L differs.
+    RegL {
+        /// The old value
+        old: u16,
+        /// The new value
+        new: u16,
+    },
+    /// Register F differs.
+    RegF {
+        /// The old value
+        old: f64,
+        /// The new value
+        new: f64,
+    },
+    /// Register Ch differs.
+    RegCh {
+        /// The old value
+        old: char,
+        /// The new value
+        new: char,
+    },
+    /// Register ř differs.
+    RegŘ {
+        /// The old value
+        old: [i8; 37],
+        /// The new value
+        new: [i8; 37],
+    },
+    /// Register ß differs.
+    Regß {
+        /// The old value
+        old: ConstantSizeString,
+        /// The new value
+        new: ConstantSizeString,
+    },
+    /// Register Ω differs.
+    RegΩ {
+        /// The old value
+        old: Ω,
+        /// The new value
+        new: Ω,
+    },
+    /// The number register differs.
+    NumReg {
+        /// The old value
+        old: i32,
+        /// The new value
+        new: i32,
+    },
+    /// The execution pointer differs.
+    RegEp {
+        /// The old value
+        old: u16,
+        /// The new value
+        new: u16,
+    },
+    /// The dot pointer differs.
+    RegDp {
+        /// The old value
+        old: u16,
+        /// The new value
+        new: u16,
+    },
+    /// The flag differs.
+    Flag {
+        /// The old value
+        old: bool,
+        /// The new value
+        new: bool,
+    },
+    /// Debug mode differs.
+    DebugMode {
+        /// The old value
+        old: bool,
+        /// The new value
+        new: bool,
+    },
+    /// Whether the machine is halted differs.
+    Halted {
+        /// The old value
+        old: bool,
+        /// The new value
+        new: bool,
+    },
+    /// Why the machine halted differs.
+    HaltedBy {
+        /// The old value
+        old: Option<HaltReason>,
+        /// The new value
+        new: Option<HaltReason>,
+    },
+    /// The state of the pseudorandom number generator differs.
+    RngState {
+        /// The old value
+        old: u64,
+        /// The new value
+        new: u64,
+    },
+    /// A contiguous range of memory differs.
+    Memory {
+        /// The address of the first differing byte
+        start: u16,
+        /// The old bytes
+        old: Vec<u8>,
+        /// The new bytes
+        new: Vec<u8>,
+    },
+    /// The used part of the stack differs.
+    Stack {
+        /// The old stack contents
+        old: Vec<u8>,
+        /// The new stack contents
+        new: Vec<u8>,
+    },
+}
+
+impl Machine {
+    /// Reports how `other` differs from `self`.
+    ///
+    /// Registers are reported individually, differing memory
+    /// bytes are grouped into contiguous ranges and the stack
+    /// is reported as a whole if any of its used bytes differ.
+    ///
+    /// Returns an empty [`Vec`] if the states are identical.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{machine::diff::StateChange, Machine};
+    /// let before = Machine::default();
+    /// let mut after = before.clone();
+    ///
+    /// after.reg_a = 5;
+    /// after.memory[10] = 1;
+    /// after.memory[11] = 2;
+    ///
+    /// assert_eq!(
+    ///     before.diff(&after),
+    ///     [
+    ///         StateChange::RegA { old: 0, new: 5 },
+    ///         StateChange::Memory { start: 10, old: vec![0, 0], new: vec![1, 2] },
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn diff(&self, other: &Self) -> Vec<StateChange> {
+        /// Pushes a change if the field differs between the machines.
+        macro_rules! field {
+            ($changes:ident, $field:ident => $variant:ident) => {
+                if self.$field != other.$field {
+                    $changes.push(StateChange::$variant {
+                        old: self.$field.clone(),
+                        new: other.$field.clone(),
+                    });
+                }
+            };
+        }
+
+        let mut changes = Vec::new();
+
+        field!(changes, reg_a => RegA);
+        field!(changes, reg_b => RegB);
+        field!(changes, reg_L => RegL);
+        field!(changes, reg_f => RegF);
+        field!(changes, reg_ch => RegCh);
+        field!(changes, reg_ř => RegŘ);
+        field!(changes, reg_ß => Regß);
+        field!(changes, reg_Ω => RegΩ);
+        field!(changes, num_reg => NumReg);
+        field!(changes, reg_ep => RegEp);
+        field!(changes, reg_dp => RegDp);
+        field!(changes, flag => Flag);
+        field!(changes, debug_mode => DebugMode);
+        field!(changes, halted => Halted);
+        field!(changes, halted_by => HaltedBy);
+        field!(changes, rng_state => RngState);
+
+        let mut start = None;
+        for (i, (old, new)) in self.memory.iter().zip(other.memory.iter()).enumerate() {
+            match (start, old == new) {
+                (None, false) => start = Some(i),
+                (Some(s), true) => {
+                    changes.push(self.memory_change(other, s, i));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        if let Some(s) = start {
+            changes.push(self.memory_change(other, s, self.memory.len()));
+        }
+
+        if self.stack.vec != other.stack.vec {
+            changes.push(StateChange::Stack {
+                old: self.stack.vec.clone(),
+                new: other.stack.vec.clone(),
+            });
+        }
+
+        changes
+    }
+
+    /// Makes a [`StateChange::Memory`] for the memory range `start..end`.
+    #[allow(clippy::cast_possible_truncation, clippy::indexing_slicing)]
+    fn memory_change(&self, other: &Self, start: usize, end: usize) -> StateChange {
+        StateChange::Memory {
+            start: start as u16,
+            old: self.memory[start..end].to_vec(),
+            new: other.memory[start..end].to_vec(),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Loading programs from and saving memory to files.
+//!
+//! More info at [`Machine::load_file`] and [`Machine::save_region`].
+
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use super::Machine;
+
+impl Machine {
+    /// Returns the memory from `start` up to (but not including) `end`.
+    ///
+    /// Returns an empty slice if `start` is after `end`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.load_bytes(b"abc", 10);
+    ///
+    /// assert_eq!(machine.dump_memory(10, 13), b"abc");
+    /// assert_eq!(machine.dump_memory(13, 10), b"");
+    /// ```
+    #[must_use]
+    pub fn dump_memory(&self, start: u16, end: u16) -> &[u8] {
+        self.memory
+            .get(start as usize..end as usize)
+            .unwrap_or_default()
+    }
+
+    /// Loads the raw bytes of the file at `path` into the machine
+    /// at the specified offset, like [`load_bytes`](Self::load_bytes).
+    ///
+    /// Returns the offset after the loaded bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or one of kind
+    /// [`InvalidInput`](ErrorKind::InvalidInput) if it doesn't fit in memory
+    /// (then nothing is loaded).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// let path = std::env::temp_dir().join("esoteric_vm_load_file.evm");
+    ///
+    /// let mut compiler = Machine::default();
+    /// let end = compiler.load(&esoteric_assembly! { pushi 7; popa; Ωtheendisnear; Ωskiptothechase; }, 0);
+    /// compiler.save_region(&path, 0, end)?;
+    ///
+    /// let mut machine = Machine::default();
+    /// assert_eq!(machine.load_file(&path, 0)?, end);
+    /// assert_eq!(machine.run(), 7);
+    ///
+    /// assert!(machine.load_file(&path, 0xFFFF - 2).is_err());
+    /// # std::fs::remove_file(path)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load_file(&mut self, path: &Path, offset: u16) -> Result<u16> {
+        let bytes = fs::read(path)?;
+
+        self.load_bytes(&bytes, offset).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "the file doesn't fit in the machine's memory",
+            )
+        })
+    }
+
+    /// Writes the memory from `start` up to (but not including) `end`
+    /// (see [`dump_memory`](Self::dump_memory)) to the file at `path`,
+    /// replacing it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save_region(&self, path: &Path, start: u16, end: u16) -> Result<()> {
+        fs::write(path, self.dump_memory(start, end))
+    }
+}
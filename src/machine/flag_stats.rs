@@ -0,0 +1,106 @@
+//! Statistics about which instructions set the flag.
+//!
+//! More info at [`FlagStats`].
+
+use super::Machine;
+use crate::instruction::{Instruction, InstructionKind};
+
+/// Counts how often each kind of instruction set the flag.
+///
+/// An instruction counts as setting the flag if the flag was
+/// cleared before it was executed and set afterwards.
+///
+/// Enable it by setting [`Machine::flag_stats`] to `Some(FlagStats::default())`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{esoteric_assembly, instruction::InstructionKind, machine::flag_stats::FlagStats, Machine};
+/// let mut machine = Machine::default();
+/// machine.load(
+///     &esoteric_assembly! {
+///         0: popa;
+///         1: clflag;
+///         2: popa;
+///         3: Ωtheendisnear;
+///         4: Ωskiptothechase;
+///     },
+///     0,
+/// );
+/// machine.flag_stats = Some(FlagStats::default());
+/// machine.run();
+///
+/// assert_eq!(machine.flag_set_count(), 2);
+/// assert_eq!(
+///     machine.flag_stats.as_ref().map(FlagStats::histogram),
+///     Some(vec![(InstructionKind::Popa, 2)])
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlagStats {
+    /// How many times each opcode set the flag
+    counts: Box<[u64; 256]>,
+}
+
+impl Default for FlagStats {
+    fn default() -> Self {
+        Self {
+            counts: Box::new([0; 256]),
+        }
+    }
+}
+
+impl FlagStats {
+    /// Counts an instruction that set the flag.
+    pub(crate) fn record(&mut self, instruction: Instruction) {
+        #[allow(clippy::indexing_slicing)]
+        let count = &mut self.counts[InstructionKind::from(instruction) as usize];
+        *count = count.saturating_add(1);
+    }
+
+    /// Returns how many times the flag was set in total.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts
+            .iter()
+            .fold(0, |total, count| total.saturating_add(*count))
+    }
+
+    /// Returns how many times an instruction of `kind` set the flag.
+    #[must_use]
+    pub fn count(&self, kind: InstructionKind) -> u64 {
+        self.counts.get(kind as usize).copied().unwrap_or_default()
+    }
+
+    /// Returns every instruction kind that set the flag with its count,
+    /// the most frequent first.
+    #[must_use]
+    pub fn histogram(&self) -> Vec<(InstructionKind, u64)> {
+        let mut histogram: Vec<_> = (0..=u8::MAX)
+            .filter_map(InstructionKind::from_repr)
+            .map(|kind| (kind, self.count(kind)))
+            .filter(|&(_, count)| count != 0)
+            .collect();
+
+        histogram.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        histogram
+    }
+}
+
+impl Machine {
+    /// Returns how many times the flag was set while
+    /// [`flag_stats`](Self::flag_stats) was enabled.
+    ///
+    /// Returns 0 if it's disabled.
+    #[must_use]
+    pub fn flag_set_count(&self) -> u64 {
+        self.flag_stats.as_ref().map_or(0, FlagStats::total)
+    }
+
+    /// Resets the flag statistics if they're enabled.
+    pub fn reset_flag_stats(&mut self) {
+        if let Some(stats) = &mut self.flag_stats {
+            *stats = FlagStats::default();
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Why a machine halted.
+//!
+//! More info at [`Machine::halt_reason`].
+
+use super::Machine;
+
+/// Why a machine halted.
+///
+/// Returned by [`Machine::halt_reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum HaltReason {
+    /// `Ωskiptothechase` was executed with the feeling of impending doom
+    Doom,
+    /// `halt` was executed
+    Halt,
+    /// Arithmetic overflowed in [`ArithmeticMode::Trap`](super::arithmetic::ArithmeticMode::Trap)
+    Trap,
+    /// [`Machine::halted`] was set from outside of the machine
+    Other,
+}
+
+impl Machine {
+    /// Returns why the machine halted, or [`None`] if it isn't halted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::{halt::HaltReason, RunOutcome}, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: Ωtheendisnear;
+    ///         1: jmp -3;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(machine.run_for(10), RunOutcome::LimitReached);
+    /// assert_eq!(machine.halt_reason(), None);
+    ///
+    /// machine.load(&esoteric_assembly! { Ωskiptothechase; }, 4);
+    /// machine.reg_ep = 4;
+    /// machine.run();
+    /// assert_eq!(machine.halt_reason(), Some(HaltReason::Doom));
+    ///
+    /// machine.reset();
+    /// machine.halted = true;
+    /// assert_eq!(machine.halt_reason(), Some(HaltReason::Other));
+    /// ```
+    #[must_use]
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        self.halted
+            .then(|| self.halted_by.unwrap_or(HaltReason::Other))
+    }
+
+    /// Halts the machine for `reason`.
+    pub(super) const fn halt(&mut self, reason: HaltReason) {
+        self.halted = true;
+        self.halted_by = Some(reason);
+    }
+}
@@ -0,0 +1,283 @@
+//! Pluggable input and output.
+//!
+//! More info at [`MachineIo`].
+
+use std::{
+    io::{self, BufRead, Read, Write},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Where a [`Machine`](super::Machine) reads input from and writes output to.
+///
+/// Every IO instruction goes through it, so implementing it
+/// lets a machine run without a terminal (e.g. in a GUI or in tests).
+/// The default is [`StdIo`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{esoteric_assembly, machine::io::MachineIo, Machine};
+/// # use std::io;
+/// /// Input that's always `!` and output that's dropped.
+/// struct Bangs;
+///
+/// impl MachineIo for Bangs {
+///     fn write(&mut self, _bytes: &[u8]) -> io::Result<()> {
+///         Ok(())
+///     }
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         buf.fill(b'!');
+///         Ok(buf.len())
+///     }
+///     fn at_eof(&mut self) -> bool {
+///         false
+///     }
+/// }
+///
+/// let mut machine = Machine::default().with_io(Bangs);
+/// machine.load(
+///     &esoteric_assembly! {
+///         0: getchar;
+///         1: Ωtheendisnear;
+///         2: Ωskiptothechase;
+///     },
+///     0,
+/// );
+/// machine.run();
+///
+/// assert_eq!(machine.reg_ch, '!');
+/// ```
+pub trait MachineIo {
+    /// Writes all of `bytes` to the output.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Reads input into `buf`, returning how many bytes were read
+    /// (0 at the end of input).
+    ///
+    /// # Errors
+    ///
+    /// Errors if reading fails.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Returns whether the input has ended, without consuming any of it.
+    fn at_eof(&mut self) -> bool;
+
+    /// Reads a character, returning `None` if reading fails.
+    ///
+    /// By default, this reads a UTF-8 encoded character with [`read`](Self::read).
+    fn read_char(&mut self) -> Option<char> {
+        read_utf8_char(self)
+    }
+
+    /// Reads a line without the line ending (`\n` or `\r\n`).
+    ///
+    /// Returns `None` if reading fails, or if the line isn't valid UTF-8
+    /// or is longer than `max` bytes (the rest of it is read anyway).
+    ///
+    /// By default, this reads the line byte by byte with [`read`](Self::read).
+    fn read_line(&mut self, max: usize) -> Option<String> {
+        let mut line = Vec::new();
+        let mut too_long = false;
+
+        loop {
+            let mut byte = [0];
+            match self.read(&mut byte) {
+                Ok(0) => break,
+                Ok(_) if byte == [b'\n'] => break,
+                Ok(_) if line.len() < max.saturating_add(1) => line.push(byte[0]),
+                Ok(_) => too_long = true,
+                Err(_) => return None,
+            }
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        if too_long || line.len() > max {
+            return None;
+        }
+        String::from_utf8(line).ok()
+    }
+}
+
+/// Reads a single byte, returning `None` at the end of input or if reading fails.
+fn read_byte(io: &mut (impl MachineIo + ?Sized)) -> Option<u8> {
+    let mut byte = [0];
+    match io.read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        Ok(_) | Err(_) => None,
+    }
+}
+
+/// Reads a UTF-8 encoded character byte by byte, returning `None` if reading fails
+/// or the bytes aren't a valid character.
+fn read_utf8_char(io: &mut (impl MachineIo + ?Sized)) -> Option<char> {
+    let first = read_byte(io)?;
+    let len = match first.leading_ones() {
+        0 => 1,
+        len @ 2..=4 => len as usize,
+        _ => return None,
+    };
+
+    let mut bytes = [first, 0, 0, 0];
+    for byte in bytes.get_mut(1..len)? {
+        *byte = read_byte(io)?;
+    }
+    std::str::from_utf8(bytes.get(..len)?).ok()?.chars().next()
+}
+
+/// Locks a mutex, turning poisoning into an IO error.
+pub(super) fn lock<'a, T: ?Sized>(
+    mutex: &'a Mutex<T>,
+    name: &str,
+) -> io::Result<MutexGuard<'a, T>> {
+    mutex
+        .lock()
+        .map_err(|_| io::Error::other(format!("{name} lock is poisoned")))
+}
+
+/// The terminal: stdin, stdout and key presses.
+///
+/// `getchar` waits for a key press in raw mode instead of reading stdin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdIo;
+
+impl MachineIo for StdIo {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        io::stdout().write_all(bytes)
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io::stdin().read(buf)
+    }
+
+    fn at_eof(&mut self) -> bool {
+        io::stdin().lock().fill_buf().map_or(true, <[u8]>::is_empty)
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        use crossterm::{
+            event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+            terminal::{disable_raw_mode, enable_raw_mode},
+        };
+
+        enable_raw_mode().ok()?;
+
+        let c = loop {
+            match event::read() {
+                Ok(Event::Key(KeyEvent {
+                    code: KeyCode::Char(c),
+                    kind: KeyEventKind::Press,
+                    ..
+                })) => break Some(c),
+                Err(_) => break None,
+                _ => (),
+            }
+        };
+
+        disable_raw_mode().ok()?;
+        c
+    }
+}
+
+/// Input from a reader and output to a writer,
+/// using the terminal ([`StdIo`]) for the side that isn't set.
+///
+/// [`Machine::with_input`](super::Machine::with_input) and
+/// [`Machine::with_output`](super::Machine::with_output) use it for one side.
+/// To set both, pass one to [`Machine::with_io`](super::Machine::with_io).
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::{esoteric_assembly, machine::io::StreamIo, Machine};
+/// # use std::{io::Cursor, sync::{Arc, Mutex}};
+/// let output = Arc::new(Mutex::new(Vec::new()));
+/// let io = StreamIo::default()
+///     .with_input(Arc::new(Mutex::new(Cursor::new(b"!"))))
+///     .with_output(output.clone());
+/// let mut machine = Machine::default().with_io(io);
+///
+/// machine.load(
+///     &esoteric_assembly! {
+///         0: pushi b'.';
+///         2: pop 28657;
+///         5: ldidp 28657;
+///         8: getbyte;
+///         9: putbyte;
+///         10: Ωtheendisnear;
+///         11: Ωskiptothechase;
+///     },
+///     0,
+/// );
+/// machine.run();
+///
+/// assert_eq!(*output.lock().unwrap(), b"!");
+/// ```
+#[derive(Default)]
+pub struct StreamIo {
+    /// The reader (stdin if `None`)
+    input: Option<Arc<Mutex<dyn Read + Send>>>,
+    /// A byte that was read ahead by [`at_eof`](MachineIo::at_eof)
+    peeked: Option<u8>,
+    /// The writer (stdout if `None`)
+    output: Option<Arc<Mutex<dyn Write + Send>>>,
+}
+
+impl StreamIo {
+    /// Reads input from `input` instead of the terminal.
+    #[must_use]
+    pub fn with_input(mut self, input: Arc<Mutex<dyn Read + Send>>) -> Self {
+        self.input = Some(input);
+        self.peeked = None;
+        self
+    }
+
+    /// Writes output to `output` instead of stdout.
+    #[must_use]
+    pub fn with_output(mut self, output: Arc<Mutex<dyn Write + Send>>) -> Self {
+        self.output = Some(output);
+        self
+    }
+}
+
+impl MachineIo for StreamIo {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match &self.output {
+            Some(output) => lock(output, "output writer")?.write_all(bytes),
+            None => StdIo.write(bytes),
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let (Some(first), Some(byte)) = (buf.first_mut(), self.peeked.take()) {
+            *first = byte;
+            return Ok(1);
+        }
+        match &self.input {
+            Some(input) => lock(input, "input reader")?.read(buf),
+            None => StdIo.read(buf),
+        }
+    }
+
+    fn at_eof(&mut self) -> bool {
+        if self.input.is_none() {
+            return StdIo.at_eof();
+        }
+        if self.peeked.is_none() {
+            self.peeked = read_byte(self);
+        }
+        self.peeked.is_none()
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        if self.input.is_none() {
+            return StdIo.read_char();
+        }
+        read_utf8_char(self)
+    }
+}
@@ -0,0 +1,582 @@
+//! An execution journal for stepping backwards.
+//!
+//! More info at [`Machine::step_back`].
+
+use std::{error::Error, fmt};
+
+use super::{
+    decoder::Decoder, diff::StateChange, halt::HaltReason, omega::Ω, wrapping_address, Machine,
+    RunError,
+};
+use crate::{instruction::Instruction, utils::constant_size_string::ConstantSizeString};
+
+/// A history of executed instructions that can be undone.
+///
+/// Enable it by setting [`Machine::journal`] to `Some(Journal::default())`.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    /// The undo records, oldest first.
+    records: Vec<UndoRecord>,
+}
+
+impl Journal {
+    /// Returns the undo records, oldest first.
+    #[must_use]
+    pub fn records(&self) -> &[UndoRecord] {
+        &self.records
+    }
+
+    /// Forgets all recorded history.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+/// What one executed instruction changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoRecord {
+    /// The executed instruction
+    instruction: Instruction,
+    /// Changed registers and memory ranges
+    changes: Vec<StateChange>,
+    /// The used space of the stack before the instruction
+    stack_len: usize,
+    /// The bytes at the top of the stack the instruction might have removed
+    stack_top: Vec<u8>,
+}
+
+impl UndoRecord {
+    /// Returns the executed instruction.
+    #[must_use]
+    pub const fn instruction(&self) -> Instruction {
+        self.instruction
+    }
+
+    /// Returns the registers and memory ranges the instruction changed.
+    ///
+    /// Stack changes aren't included.
+    #[must_use]
+    pub fn changes(&self) -> &[StateChange] {
+        &self.changes
+    }
+
+    /// Returns whether the instruction can be undone.
+    ///
+    /// IO instructions can't be undone, since their input
+    /// was consumed or their output was already written.
+    #[must_use]
+    pub const fn is_reversible(&self) -> bool {
+        !self.instruction.is_io() && !matches!(self.instruction, Instruction::ΩSetSentience(false))
+    }
+}
+
+/// An error from [`Machine::step_back`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepBackError {
+    /// The journal isn't enabled.
+    NotRecording,
+    /// There's no recorded instruction left to undo.
+    NothingToUndo,
+    /// The last recorded instruction did IO, which can't be undone.
+    Irreversible(Instruction),
+}
+
+impl fmt::Display for StepBackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRecording => f.write_str("the journal isn't enabled"),
+            Self::NothingToUndo => f.write_str("there's nothing to undo"),
+            Self::Irreversible(instruction) => write!(f, "{instruction:?} can't be undone"),
+        }
+    }
+}
+
+impl Error for StepBackError {}
+
+/// The registers of a machine, used to find out which ones an instruction changed.
+#[allow(non_snake_case)]
+struct Registers {
+    /// register a
+    reg_a: u8,
+    /// register b
+    reg_b: i16,
+    /// register L
+    reg_L: u16,
+    /// register f
+    reg_f: f64,
+    /// register ch
+    reg_ch: char,
+    /// register ř
+    reg_ř: [i8; 37],
+    /// register ß
+    reg_ß: ConstantSizeString,
+    /// register Ω
+    reg_Ω: Ω,
+    /// number register
+    num_reg: i32,
+    /// execution pointer
+    reg_ep: u16,
+    /// dot pointer
+    reg_dp: u16,
+    /// overflow/error flag
+    flag: bool,
+    /// debug mode
+    debug_mode: bool,
+    /// whether the machine is halted
+    halted: bool,
+    /// why the machine halted
+    halted_by: Option<HaltReason>,
+    /// state of the pseudorandom number generator
+    rng_state: u64,
+}
+
+impl Registers {
+    /// Copies the registers of a machine.
+    fn of(machine: &Machine) -> Self {
+        Self {
+            reg_a: machine.reg_a,
+            reg_b: machine.reg_b,
+            reg_L: machine.reg_L,
+            reg_f: machine.reg_f,
+            reg_ch: machine.reg_ch,
+            reg_ř: machine.reg_ř,
+            reg_ß: machine.reg_ß.clone(),
+            reg_Ω: machine.reg_Ω.clone(),
+            num_reg: machine.num_reg,
+            reg_ep: machine.reg_ep,
+            reg_dp: machine.reg_dp,
+            flag: machine.flag,
+            debug_mode: machine.debug_mode,
+            halted: machine.halted,
+            halted_by: machine.halted_by,
+            rng_state: machine.rng_state,
+        }
+    }
+
+    /// Pushes a change for every register that differs in `machine`.
+    #[allow(clippy::float_cmp)]
+    fn changes(self, machine: &Machine, changes: &mut Vec<StateChange>) {
+        /// Pushes a change if the register differs.
+        macro_rules! field {
+            ($field:ident => $variant:ident) => {
+                if self.$field != machine.$field {
+                    changes.push(StateChange::$variant {
+                        old: self.$field,
+                        new: machine.$field.clone(),
+                    });
+                }
+            };
+        }
+
+        field!(reg_a => RegA);
+        field!(reg_b => RegB);
+        field!(reg_L => RegL);
+        field!(reg_f => RegF);
+        field!(reg_ch => RegCh);
+        field!(reg_ř => RegŘ);
+        field!(reg_ß => Regß);
+        field!(reg_Ω => RegΩ);
+        field!(num_reg => NumReg);
+        field!(reg_ep => RegEp);
+        field!(reg_dp => RegDp);
+        field!(flag => Flag);
+        field!(debug_mode => DebugMode);
+        field!(halted => Halted);
+        field!(halted_by => HaltedBy);
+        field!(rng_state => RngState);
+    }
+}
+
+/// The state an instruction might change besides registers.
+struct Footprint {
+    /// The memory that might be written (start address and length, wrapping around the end of memory)
+    memory: Option<(u16, usize)>,
+    /// How many bytes might be removed from the top of the stack
+    stack: usize,
+}
+
+impl Footprint {
+    /// Changes only registers.
+    const NONE: Self = Self::stack(0);
+
+    /// Might remove `amount` bytes from the top of the stack.
+    const fn stack(amount: usize) -> Self {
+        Self {
+            memory: None,
+            stack: amount,
+        }
+    }
+
+    /// Might write `len` bytes of memory starting at `start`.
+    const fn memory(start: u16, len: usize) -> Self {
+        Self {
+            memory: Some((start, len)),
+            stack: 0,
+        }
+    }
+
+    /// Returns the memory addresses that might be written.
+    fn addresses(&self) -> impl Iterator<Item = u16> {
+        let (start, len) = self.memory.unwrap_or((0, 0));
+        #[allow(clippy::cast_possible_truncation)]
+        (0..len).map(move |i| wrapping_address(start, i) as u16)
+    }
+}
+
+impl Machine {
+    /// Reverts the last instruction recorded in the [`journal`](Self::journal),
+    /// returning the reverted instruction.
+    ///
+    /// Every instruction can be reverted except IO instructions
+    /// (see [`Instruction::is_io`]) and `Ωsetsentience false`,
+    /// which writes to stderr. Their state changes are still recorded,
+    /// but their input or output can't be taken back.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the journal isn't enabled, if it's empty
+    /// or if the last instruction can't be reverted.
+    /// The machine and the journal aren't changed in that case,
+    /// so the history before an irreversible instruction stays unreachable
+    /// until the journal is cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::journal::Journal, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 7;
+    ///         2: pop 100;
+    ///     },
+    ///     0,
+    /// );
+    /// machine.journal = Some(Journal::default());
+    /// let before = machine.clone();
+    ///
+    /// machine.step();
+    /// machine.step();
+    /// assert_eq!(machine.memory[100], 7);
+    ///
+    /// machine.step_back().unwrap();
+    /// machine.step_back().unwrap();
+    /// assert!(before.diff(&machine).is_empty());
+    /// ```
+    pub fn step_back(&mut self) -> Result<Instruction, StepBackError> {
+        let journal = self.journal.as_mut().ok_or(StepBackError::NotRecording)?;
+        let record = journal.records.pop().ok_or(StepBackError::NothingToUndo)?;
+        if !record.is_reversible() {
+            let instruction = record.instruction;
+            journal.records.push(record);
+            return Err(StepBackError::Irreversible(instruction));
+        }
+
+        for change in record.changes {
+            self.revert(change);
+        }
+
+        self.stack
+            .vec
+            .truncate(record.stack_len.saturating_sub(record.stack_top.len()));
+        self.stack.vec.extend_from_slice(&record.stack_top);
+
+        Ok(record.instruction)
+    }
+
+    /// Fetches and executes one instruction,
+    /// pushing its undo record to the journal if it's enabled.
+    pub(crate) fn fetch_and_execute_recorded(&mut self) -> Result<Instruction, RunError> {
+        let record = self.undo_record_of_step()?;
+        let instruction = record.instruction;
+
+        if let Some(journal) = &mut self.journal {
+            journal.records.push(record);
+        }
+
+        Ok(instruction)
+    }
+
+    /// Fetches and executes one instruction, returning its undo record.
+    fn undo_record_of_step(&mut self) -> Result<UndoRecord, RunError> {
+        let registers = Registers::of(self);
+
+        let instruction = Decoder::new(self.memory.as_slice(), self.reg_ep).decode();
+
+        let footprint = instruction.map_or(Footprint::NONE, |i| self.footprint(i));
+
+        let memory: Vec<Option<u8>> = footprint
+            .addresses()
+            .map(|address| self.memory.get(address as usize).copied())
+            .collect();
+        let stack_len = self.stack.used_space();
+        let stack_top = self
+            .stack
+            .vec
+            .get(stack_len.saturating_sub(footprint.stack)..)
+            .unwrap_or_default()
+            .to_vec();
+
+        let instruction = self.fetch_and_execute()?;
+
+        let mut changes = Vec::new();
+        registers.changes(self, &mut changes);
+
+        for (address, old) in footprint.addresses().zip(memory) {
+            let (Some(old), Some(&new)) = (old, self.memory.get(address as usize)) else {
+                continue;
+            };
+            if old == new {
+                continue;
+            }
+
+            let extends_last = matches!(
+                changes.last(),
+                Some(StateChange::Memory { start, old, .. })
+                    if (*start as usize).saturating_add(old.len()) == address as usize
+            );
+            if let (
+                true,
+                Some(StateChange::Memory {
+                    old: olds,
+                    new: news,
+                    ..
+                }),
+            ) = (extends_last, changes.last_mut())
+            {
+                olds.push(old);
+                news.push(new);
+            } else {
+                changes.push(StateChange::Memory {
+                    start: address,
+                    old: vec![old],
+                    new: vec![new],
+                });
+            }
+        }
+
+        Ok(UndoRecord {
+            instruction,
+            changes,
+            stack_len,
+            stack_top,
+        })
+    }
+
+    /// Restores the old value of a change.
+    fn revert(&mut self, change: StateChange) {
+        match change {
+            StateChange::RegA { old, .. } => self.reg_a = old,
+            StateChange::RegB { old, .. } => self.reg_b = old,
+            StateChange::RegL { old, .. } => self.reg_L = old,
+            StateChange::RegF { old, .. } => self.reg_f = old,
+            StateChange::RegCh { old, .. } => self.reg_ch = old,
+            StateChange::RegŘ { old, .. } => self.reg_ř = old,
+            StateChange::Regß { old, .. } => self.reg_ß = old,
+            StateChange::RegΩ { old, .. } => self.reg_Ω = old,
+            StateChange::NumReg { old, .. } => self.num_reg = old,
+            StateChange::RegEp { old, .. } => self.reg_ep = old,
+            StateChange::RegDp { old, .. } => self.reg_dp = old,
+            StateChange::Flag { old, .. } => self.flag = old,
+            StateChange::DebugMode { old, .. } => self.debug_mode = old,
+            StateChange::Halted { old, .. } => self.halted = old,
+            StateChange::HaltedBy { old, .. } => self.halted_by = old,
+            StateChange::RngState { old, .. } => self.rng_state = old,
+            StateChange::Memory { start, old, .. } => {
+                if let Some(memory) = self
+                    .memory
+                    .get_mut(start as usize..(start as usize).saturating_add(old.len()))
+                {
+                    memory.copy_from_slice(&old);
+                }
+            }
+            // the journal restores the stack by itself
+            StateChange::Stack { .. } => (),
+        }
+    }
+
+    /// Returns the state that executing `instruction` might change besides registers.
+    #[allow(clippy::too_many_lines, clippy::match_same_arms)]
+    const fn footprint(&self, instruction: Instruction) -> Footprint {
+        #[allow(clippy::enum_glob_use)]
+        use Instruction::*;
+
+        match instruction {
+            Nop => Footprint::NONE,
+
+            Ldar(_) => Footprint::NONE,
+            LdarL => Footprint::NONE,
+            StarL => Footprint::memory(self.reg_L, 1),
+            MemFillA(data0, data1) => Footprint::memory(data0, data1 as usize),
+            MemCpy(data0, _, data2) => Footprint::memory(data0, data2 as usize),
+            MemSet(data0, _, data2) => Footprint::memory(data0, data2 as usize),
+            Shuffle(data0, data1) => Footprint::memory(data0, data1 as usize),
+            Sba => Footprint::NONE,
+            SwapAB => Footprint::NONE,
+            MovAB => Footprint::NONE,
+            MovBA => Footprint::NONE,
+            RotlA(_) => Footprint::NONE,
+            RotrA(_) => Footprint::NONE,
+            AToDec(data) => Footprint::memory(data, 3),
+
+            Clř => Footprint::NONE,
+            Dumpř(data) => Footprint::memory(data, 37),
+            Movař(_) => Footprint::NONE,
+            Setř(_, _) => Footprint::NONE,
+            Setiř(_, _) => Footprint::NONE,
+            Ldř(_) => Footprint::NONE,
+            Ldiř(_) => Footprint::NONE,
+
+            Clß => Footprint::NONE,
+            Dumpß(data) => Footprint::memory(data, self.reg_ß.len()),
+            Writeß(data0, _) => Footprint::memory(data0, 1),
+            Movaß(_) => Footprint::NONE,
+            Setß(_, _) => Footprint::NONE,
+            Setiß(_, _) => Footprint::NONE,
+            Ldß(_) => Footprint::NONE,
+            Pushß => Footprint::stack(1),
+            Popß => Footprint::NONE,
+            Lenßa => Footprint::NONE,
+            Lenßl => Footprint::NONE,
+            Jßeq(_, _) => Footprint::NONE,
+
+            Ldidp(_) => Footprint::NONE,
+            CheckDp => Footprint::NONE,
+            Ldinum(_) => Footprint::NONE,
+            Ldif(_) => Footprint::NONE,
+
+            ΩChoiceSet(_) => Footprint::NONE,
+            ΩChoiceGetA => Footprint::NONE,
+
+            ΩGainAPolymorphicDesires => Footprint::NONE,
+            ΩLoseAPolymorphicDesires => Footprint::NONE,
+            ΩPushPolymorphicDesires => Footprint::NONE,
+
+            ΩTheEndIsNear => Footprint::NONE,
+            ΩSkipToTheChase => Footprint::NONE,
+
+            ΩSetSentience(_) => Footprint::NONE,
+            ΩSetPaperclipProduction(_) => Footprint::NONE,
+
+            AddBL => Footprint::NONE,
+            SubBL => Footprint::NONE,
+            AddiL(_) => Footprint::NONE,
+            SubiL(_) => Footprint::NONE,
+            IncL => Footprint::NONE,
+            DecL => Footprint::NONE,
+            MulBL => Footprint::NONE,
+            DivBL => Footprint::NONE,
+            ModBL => Footprint::NONE,
+
+            NotL => Footprint::NONE,
+
+            AndBL => Footprint::NONE,
+            OrBL => Footprint::NONE,
+            XorBL => Footprint::NONE,
+            PopcntL => Footprint::NONE,
+            CtzL => Footprint::NONE,
+            ClzL => Footprint::NONE,
+
+            CmpLB => Footprint::NONE,
+            ClampL => Footprint::NONE,
+            CmpEqLB => Footprint::NONE,
+            CmpLtLB => Footprint::NONE,
+            CmpGtLB => Footprint::NONE,
+
+            TgFlag => Footprint::NONE,
+            ClFlag => Footprint::NONE,
+            PushFlagClear => Footprint::NONE,
+            PopFlag => Footprint::stack(1),
+
+            AddF(_) => Footprint::NONE,
+            SubF(_) => Footprint::NONE,
+            MulF(_) => Footprint::NONE,
+            DivF(_) => Footprint::NONE,
+            ModF(_) => Footprint::NONE,
+            SqrtF => Footprint::NONE,
+            FloorF => Footprint::NONE,
+            CeilF => Footprint::NONE,
+            RoundF => Footprint::NONE,
+            FtoL => Footprint::NONE,
+            LtoF => Footprint::NONE,
+
+            StackAlloc(_) => Footprint::NONE,
+            StackDealloc(amount) => Footprint::stack(amount as usize),
+            StackDup => Footprint::NONE,
+            StackSwap => Footprint::stack(2),
+            StackClear => Footprint::stack(self.stack.used_space()),
+
+            Push(_) => Footprint::NONE,
+            Pushi(_) => Footprint::NONE,
+            PushiU16(_) => Footprint::NONE,
+            Pop(data) => Footprint {
+                memory: Some((data, 1)),
+                stack: 1,
+            },
+            PopMem(data0, data1) => Footprint {
+                memory: Some((data0, data1 as usize)),
+                stack: data1 as usize,
+            },
+
+            Popa => Footprint::stack(1),
+            Pusha => Footprint::NONE,
+
+            Popb => Footprint::stack(2),
+            Pushb => Footprint::NONE,
+
+            PopL => Footprint::stack(2),
+            PushL => Footprint::NONE,
+            SpToL => Footprint::NONE,
+            EpToL => Footprint::NONE,
+
+            Popf => Footprint::stack(8),
+            Pushf => Footprint::NONE,
+
+            Popch => Footprint::stack(4),
+            Pushch => Footprint::NONE,
+
+            Popnum => Footprint::stack(4),
+            Pushnum => Footprint::NONE,
+
+            Popep => Footprint::stack(2),
+            Zpopep => Footprint::stack(2),
+            Ppopep => Footprint::stack(2),
+            Npopep => Footprint::stack(2),
+            Fpopep => Footprint::stack(2),
+            Zapopep => Footprint::stack(2),
+            Dpopep => Footprint::stack(2),
+            JmpL => Footprint::NONE,
+            Jmp(_) => Footprint::NONE,
+            Jz(_) => Footprint::NONE,
+            Jnz(_) => Footprint::NONE,
+            Jf(_) => Footprint::NONE,
+            FjmpClear(_) => Footprint::NONE,
+            Call(_) => Footprint::NONE,
+            Ret => Footprint::stack(2),
+            Halt => Footprint::NONE,
+            BeqAi(_, _) => Footprint::NONE,
+            JmemEq(_, _, _) => Footprint::NONE,
+
+            GetChar => Footprint::NONE,
+            GetLine => Footprint::NONE,
+            ReadNum => Footprint::NONE,
+            GetByte => Footprint::NONE,
+            AtEof => Footprint::NONE,
+
+            WriteChar => Footprint::NONE,
+            PutByte => Footprint::NONE,
+            WriteNum => Footprint::NONE,
+            WriteLineß => Footprint::NONE,
+            WriteLine(_) => Footprint::NONE,
+            WriteLineIfFlag(_) => Footprint::NONE,
+            WriteStack(_, _) => Footprint::NONE,
+
+            ToggleDebug => Footprint::NONE,
+            DebugMachineState => Footprint::NONE,
+            DebugMachineStateCompact => Footprint::NONE,
+            DebugMemoryRegion(_, _) => Footprint::NONE,
+            DebugStackRegion(_, _) => Footprint::NONE,
+            ShowChoice => Footprint::NONE,
+        }
+    }
+}
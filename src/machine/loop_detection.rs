@@ -0,0 +1,80 @@
+//! Heuristic infinite loop detection.
+//!
+//! More info at [`Machine::run_with_loop_detection`].
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{Machine, RunOutcome};
+
+impl Machine {
+    /// Runs the machine until it halts or it's likely stuck in an infinite loop.
+    ///
+    /// Before every step, the execution pointer and the [`state_checksum`](Self::state_checksum)
+    /// are compared with the ones of the last `window` steps. If the same state was already seen
+    /// `repeats` times (at least once) in that window, the machine is stopped before the step
+    /// and [`RunOutcome::LikelyInfiniteLoop`] is returned. Like with [`run_for`](Self::run_for),
+    /// running the machine again continues where it left off.
+    ///
+    /// This only catches loops that exactly repeat the state, so it misses:
+    ///
+    /// - loops that change any state every iteration (such as a counter,
+    ///   a growing stack or memory writes), even if they never end
+    /// - loops with more than `window` steps per iteration
+    ///
+    /// and it can be fooled by loops that wait on input, since input isn't part of the state.
+    ///
+    /// Checksumming the state every step makes this much slower than [`run`](Self::run).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an invalid opcode (instruction) is stumbled upon, like [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::RunOutcome, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: nop;
+    ///         1: jmpl; // register L is 0
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(
+    ///     machine.run_with_loop_detection(16, 3),
+    ///     RunOutcome::LikelyInfiniteLoop
+    /// );
+    /// assert_eq!(machine.reg_ep, 0);
+    /// ```
+    pub fn run_with_loop_detection(&mut self, window: usize, repeats: usize) -> RunOutcome {
+        let mut recent = VecDeque::with_capacity(window);
+        let mut seen: HashMap<(u16, u64), usize> = HashMap::new();
+
+        while !self.halted {
+            let state = (self.reg_ep, self.state_checksum());
+            let count = seen.entry(state).or_default();
+            if *count >= repeats.max(1) {
+                return RunOutcome::LikelyInfiniteLoop;
+            }
+            *count = count.saturating_add(1);
+
+            recent.push_back(state);
+            if recent.len() > window {
+                if let Some(oldest) = recent.pop_front() {
+                    match seen.get_mut(&oldest) {
+                        Some(count) if *count > 1 => *count = count.saturating_sub(1),
+                        Some(_) | None => {
+                            seen.remove(&oldest);
+                        }
+                    }
+                }
+            }
+
+            self.step();
+        }
+
+        RunOutcome::Halted(self.reg_a)
+    }
+}
@@ -2,18 +2,45 @@
 //!
 //! Read the docs of [`Machine`] for more info.
 
+pub mod arithmetic;
+pub mod checksum;
+pub mod counters;
+pub mod decoder;
+pub mod diff;
+pub mod file;
+pub mod flag_stats;
+pub mod halt;
+pub mod io;
+pub mod journal;
+pub mod loop_detection;
 pub mod omega;
+pub mod outcome;
+pub mod parts;
+mod rng;
+#[cfg(feature = "serde")]
+mod serialization;
 pub mod stack;
-
+pub mod trace;
+
+use arithmetic::ArithmeticMode;
+use decoder::Decoder;
+use flag_stats::FlagStats;
+use halt::HaltReason;
+use io::{lock, MachineIo, StreamIo};
+use journal::Journal;
 use omega::Ω;
+use parts::MachineParts;
 use stack::Stack;
 use std::{
-    fmt::Debug,
+    error::Error,
+    fmt::{self, Debug},
     io::{Read, Write},
     mem::transmute,
     process::{ExitCode, Termination},
     ptr::copy,
+    sync::{Arc, Mutex},
 };
+use trace::Traced;
 
 use crate::{
     instruction::{DataOrInstruction, Instruction, InstructionKind},
@@ -23,6 +50,24 @@ use crate::{
     },
 };
 
+/// The size of the memory of a [`Machine`] in bytes.
+pub const MEMORY_SIZE: usize = 0xFFFF;
+
+/// The message [`Machine::step`] and [`Machine::run`] panic with on invalid instructions.
+const INVALID_OPCODE_MESSAGE: &str =
+    "EsotericVm.RuntimeException.FetchInstruction.NilInstruction.InvalidOpcode (bad instruction code)";
+
+/// Returns the range of `len` bytes from `start`, clamped to the end of memory.
+fn memory_range(start: u16, len: u16) -> (usize, usize) {
+    let start = (start as usize).min(MEMORY_SIZE);
+    (start, start.saturating_add(len as usize).min(MEMORY_SIZE))
+}
+
+/// Returns the address `offset` bytes after `start`, wrapping around the end of memory.
+const fn wrapping_address(start: u16, offset: usize) -> usize {
+    (start as usize).wrapping_add(offset) % MEMORY_SIZE
+}
+
 /// An esoteric virtual machine.
 ///
 /// Create a new machine with [`Machine::new`] and load
@@ -72,8 +117,14 @@ use crate::{
 /// machine
 /// # }
 /// ```
-#[allow(non_snake_case)]
+#[allow(
+    non_snake_case,
+    clippy::struct_excessive_bools,
+    // deserializing checks the memory's length and register ß's encoding
+    clippy::unsafe_derive_deserialize
+)]
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Machine {
     /// register a (used as the machine's exit code)
     pub reg_a: u8,
@@ -86,6 +137,7 @@ pub struct Machine {
     /// register ch (ch is one letter in Czech, therefore it's valid)
     pub reg_ch: char,
     /// register ř
+    #[cfg_attr(feature = "serde", serde(with = "serialization::array"))]
     pub reg_ř: [i8; 37],
     /// register ß
     pub reg_ß: ConstantSizeString,
@@ -107,39 +159,82 @@ pub struct Machine {
 
     /// whether the machine is halted (can't run anymore and is finished)
     pub halted: bool,
+    /// why the machine halted (see [`halt_reason`](Self::halt_reason),
+    /// `None` if it isn't halted or [`halted`](Self::halted) was set from outside)
+    pub halted_by: Option<HaltReason>,
+    /// how arithmetic overflow is handled
+    pub arithmetic_mode: ArithmeticMode,
+    /// state of the pseudorandom number generator used by `shuffle`
+    /// (set it to seed the generator, which is deterministic)
+    pub rng_state: u64,
 
     /// memory (should be 65K)
-    pub memory: Box<[u8; 0xFFFF]>,
+    #[cfg_attr(feature = "serde", serde(with = "serialization::memory"))]
+    pub memory: Box<[u8; MEMORY_SIZE]>,
     /// stack memory (default is 4K)
     pub stack: Stack,
+
+    /// where program input is read from and output is written
+    /// (the terminal by default, see [`StdIo`](io::StdIo))
+    ///
+    /// Clones of a machine share the same backend.
+    #[cfg_attr(feature = "serde", serde(skip, default = "serialization::default_io"))]
+    pub io: Arc<Mutex<dyn MachineIo + Send>>,
+    /// whether debug output goes to stderr instead of [`io`](Self::io)
+    pub debug_to_stderr: bool,
+    /// how many bytes of memory from the start and from the end
+    /// the [`Debug`] output of the machine shows (16 and 0 by default)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.memory[0xFFFE] = 0xAB;
+    /// machine.debug_memory_window = (2, 2);
+    ///
+    /// assert!(format!("{machine:?}").contains("memory: [0, 0, .., 0, 171]"));
+    /// ```
+    pub debug_memory_window: (usize, usize),
+    /// whether IO instructions are skipped (for benchmarking)
+    ///
+    /// IO instructions still set the flag if the dot pointer is invalid, like they
+    /// normally do, but don't read or write anything. This changes the behavior of programs
+    /// (there's no output and no input is read), so it's only meant for measuring
+    /// the cost of executing instructions without the cost of IO.
+    pub suppress_io: bool,
+
+    /// execution journal used by [`step_back`](Self::step_back) (disabled if `None`)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub journal: Option<Journal>,
+    /// how many instructions were executed (see [`reset_counters`](Self::reset_counters))
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub instructions_executed: u64,
+    /// how many times each opcode was fetched (see [`opcode_count`](Self::opcode_count))
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "serialization::zeroed_counts")
+    )]
+    pub opcode_counts: Box<[u64; 256]>,
+    /// statistics about which instructions set the flag (disabled if `None`)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub flag_stats: Option<FlagStats>,
+    /// where the execution trace is written (see [`trace_to`](Self::trace_to), disabled if `None`)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub trace: Option<Arc<Mutex<dyn Write + Send>>>,
 }
 
 impl Default for Machine {
     fn default() -> Self {
-        let memory: Box<[u8]> = vec![0; 0xFFFF].into_boxed_slice();
-        let memory_ptr: *mut [u8; 0xFFFF] = Box::into_raw(memory).cast();
+        let memory: Box<[u8]> = vec![0; MEMORY_SIZE].into_boxed_slice();
+        let memory_ptr: *mut [u8; MEMORY_SIZE] = Box::into_raw(memory).cast();
         // SAFETY: `memory` is a valid `Box` and has the correct length and type
-        let memory: Box<[u8; 0xFFFF]> = unsafe { Box::from_raw(memory_ptr) };
-
-        Self {
-            reg_a: 0,
-            reg_b: 0,
-            reg_L: 0,
-            reg_f: 0.0,
-            reg_ch: '\0',
-            reg_ř: [0; 37],
-            // SAFETY: An empty Vec is valid UTF-8
-            reg_ß: unsafe { ConstantSizeString::new(Vec::with_capacity(255)) },
-            reg_Ω: Ω::ZEROED,
-            num_reg: 0,
-            reg_ep: 0,
-            reg_dp: 0,
-            flag: false,
-            debug_mode: cfg!(debug_assertions),
-            halted: false,
+        let memory: Box<[u8; MEMORY_SIZE]> = unsafe { Box::from_raw(memory_ptr) };
+
+        Self::from_parts(MachineParts {
             memory,
-            stack: Stack::default(),
-        }
+            stack: Stack::default().vec,
+        })
     }
 }
 
@@ -166,12 +261,81 @@ impl Debug for Machine {
             .field("flag", &self.flag)
             .field("debug_mode", &self.debug_mode)
             .field("halted", &self.halted)
-            .field("memory", &(&self.memory).array_debug(16, 0))
+            .field("halted_by", &self.halted_by)
+            .field("arithmetic_mode", &self.arithmetic_mode)
+            .field("rng_state", &self.rng_state)
+            .field(
+                "memory",
+                &(&self.memory).array_debug(self.debug_memory_window.0, self.debug_memory_window.1),
+            )
             .field("stack", &self.stack)
-            .finish()
+            .field("debug_to_stderr", &self.debug_to_stderr)
+            .field("suppress_io", &self.suppress_io)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How [`Machine::run_for`] or [`Machine::run_with_loop_detection`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunOutcome {
+    /// The machine halted with this exit code (register A)
+    Halted(u8),
+    /// The instruction limit was reached before the machine halted
+    LimitReached,
+    /// The machine kept repeating the same state, so it's likely in an infinite loop
+    LikelyInfiniteLoop,
+}
+
+/// An error from running a [`Machine`] (returned by [`Machine::try_run`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunError {
+    /// The instruction at `at` is invalid: its opcode doesn't exist,
+    /// an operand is invalid or it goes past the end of memory
+    InvalidOpcode {
+        /// The first byte of the instruction
+        opcode: u8,
+        /// The address of the instruction
+        at: u16,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOpcode { opcode, at } => {
+                write!(
+                    f,
+                    "invalid instruction (opcode {opcode:#04x}) at address {at}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for RunError {}
+
+/// An error from loading data into a [`Machine`] (returned by [`Machine::try_load`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadError {
+    /// The item loaded at `at` would go past the end of memory
+    Overflow {
+        /// The offset the item would be loaded at
+        at: u16,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow { at } => {
+                write!(f, "data loaded at address {at} goes past the end of memory")
+            }
+        }
     }
 }
 
+impl Error for LoadError {}
+
 impl Termination for Machine {
     fn report(self) -> ExitCode {
         self.reg_a.into()
@@ -179,6 +343,295 @@ impl Termination for Machine {
 }
 
 impl Machine {
+    /// Reads program input from `input` and writes output to stdout.
+    ///
+    /// This replaces the whole [`io`](Self::io) backend, like [`with_io`](Self::with_io).
+    /// To also replace the output, pass a [`StreamIo`] with both to [`with_io`](Self::with_io).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// # use std::{io::Cursor, sync::{Arc, Mutex}};
+    /// let mut machine = Machine::default().with_input(Arc::new(Mutex::new(Cursor::new(b"!"))));
+    ///
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi b'.';
+    ///         2: pop 28657;
+    ///         5: ldidp 28657;
+    ///         8: getbyte;
+    ///         9: Ωtheendisnear;
+    ///         10: Ωskiptothechase;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(machine.run(), b'!');
+    /// ```
+    #[must_use]
+    pub fn with_input(self, input: Arc<Mutex<dyn Read + Send>>) -> Self {
+        self.with_io(StreamIo::default().with_input(input))
+    }
+
+    /// Replaces the backend that program input comes from and output goes to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::io::MachineIo, Machine};
+    /// # use std::{io, sync::{Arc, Mutex}};
+    /// /// Output into a shared buffer, and no input.
+    /// struct Captured(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl MachineIo for Captured {
+    ///     fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+    ///         self.0.lock().unwrap().extend_from_slice(bytes);
+    ///         Ok(())
+    ///     }
+    ///     fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+    ///         Ok(0)
+    ///     }
+    ///     fn at_eof(&mut self) -> bool {
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let buffer = Arc::new(Mutex::new(Vec::new()));
+    /// let mut machine = Machine::default().with_io(Captured(buffer.clone()));
+    ///
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi b'.';
+    ///         2: pop 28657;
+    ///         5: ldidp 28657;
+    ///         8: writeline 14;
+    ///         11: getline;
+    ///         12: Ωtheendisnear;
+    ///         13: Ωskiptothechase;
+    ///         14: data b"Hi\n\0";
+    ///     },
+    ///     0,
+    /// );
+    /// machine.run();
+    ///
+    /// assert_eq!(*buffer.lock().unwrap(), b"Hi\n");
+    /// assert_eq!(machine.reg_ß.as_bytes(), b"");
+    /// ```
+    #[must_use]
+    pub fn with_io(mut self, io: impl MachineIo + Send + 'static) -> Self {
+        self.io = Arc::new(Mutex::new(io));
+        self
+    }
+
+    /// Replaces the stack with an empty one that can hold `bytes` bytes
+    /// (4095 by default).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let machine = Machine::default().with_stack_capacity(8192);
+    ///
+    /// assert_eq!(machine.stack.total_space(), 8192);
+    /// ```
+    #[must_use]
+    pub fn with_stack_capacity(mut self, bytes: usize) -> Self {
+        self.stack = Stack::with_capacity(bytes);
+        self
+    }
+
+    /// Reads bytes from [`io`](Self::io), returning how many were read.
+    ///
+    /// # Errors
+    ///
+    /// Errors if reading fails or the lock is poisoned.
+    pub fn read_input(&self, buf: &mut [u8]) -> std::io::Result<usize> {
+        lock(&self.io, "IO")?.read(buf)
+    }
+
+    /// Checks if there's more input without consuming it.
+    ///
+    /// Returns `false` at the end of input or if reading fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// # use std::{io::Cursor, sync::{Arc, Mutex}};
+    /// let machine = Machine::default().with_input(Arc::new(Mutex::new(Cursor::new(b"!"))));
+    /// let mut buf = [0; 4];
+    ///
+    /// assert!(machine.peek_input());
+    /// assert!(machine.peek_input());
+    /// assert_eq!(machine.read_input(&mut buf).unwrap(), 1);
+    /// assert_eq!(buf[0], b'!');
+    /// assert!(!machine.peek_input());
+    /// ```
+    #[must_use]
+    pub fn peek_input(&self) -> bool {
+        lock(&self.io, "IO").is_ok_and(|mut io| !io.at_eof())
+    }
+
+    /// Writes program output to `output` and reads input from the terminal.
+    ///
+    /// This replaces the whole [`io`](Self::io) backend, like [`with_io`](Self::with_io).
+    /// To also replace the input, pass a [`StreamIo`] with both to [`with_io`](Self::with_io).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// # use std::sync::{Arc, Mutex};
+    /// let buffer = Arc::new(Mutex::new(Vec::new()));
+    /// let mut machine = Machine::default().with_output(buffer.clone());
+    ///
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi b'.';
+    ///         2: pop 28657;
+    ///         5: ldidp 28657;
+    ///         8: writeline 13;
+    ///         11: Ωtheendisnear;
+    ///         12: Ωskiptothechase;
+    ///         13: data b"Hello, world!\n\0";
+    ///     },
+    ///     0,
+    /// );
+    /// machine.run();
+    ///
+    /// assert_eq!(*buffer.lock().unwrap(), b"Hello, world!\n");
+    /// ```
+    #[must_use]
+    pub fn with_output(self, output: Arc<Mutex<dyn Write + Send>>) -> Self {
+        self.with_io(StreamIo::default().with_output(output))
+    }
+
+    /// Writes bytes to [`io`](Self::io).
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails or the lock is poisoned.
+    pub fn write_output(&self, bytes: &[u8]) -> std::io::Result<()> {
+        lock(&self.io, "IO")?.write(bytes)
+    }
+
+    /// Writes debug output to [`io`](Self::io),
+    /// or to stderr if [`debug_to_stderr`](Self::debug_to_stderr) is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    pub fn write_debug(&self, bytes: &[u8]) -> std::io::Result<()> {
+        if self.debug_to_stderr {
+            std::io::stderr().write_all(bytes)
+        } else {
+            self.write_output(bytes)
+        }
+    }
+
+    /// Returns the NUL-terminated string at `address`, without the NUL byte.
+    ///
+    /// Returns `None` if there's no NUL byte between `address` and the end of memory.
+    #[must_use]
+    pub fn c_str_at(&self, address: u16) -> Option<&[u8]> {
+        let bytes = self.memory.get(address as usize..)?;
+        let len = bytes.iter().position(|&byte| byte == 0)?;
+
+        bytes.get(..len)
+    }
+
+    /// Returns `len` bytes of memory from `start`.
+    ///
+    /// The slice is shorter if it would go past the end of memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.load_bytes(b"abc", 10);
+    ///
+    /// assert_eq!(machine.memory_slice(10, 3), b"abc");
+    /// assert_eq!(machine.memory_slice(0xFFFE, 3).len(), 1);
+    /// assert_eq!(machine.memory_slice(0xFFFF, 3), b"");
+    /// ```
+    #[must_use]
+    pub fn memory_slice(&self, start: u16, len: u16) -> &[u8] {
+        let (start, end) = memory_range(start, len);
+        self.memory.get(start..end).unwrap_or_default()
+    }
+
+    /// Returns `len` bytes of memory from `start` mutably.
+    ///
+    /// The slice is shorter if it would go past the end of memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.memory_mut(10, 3).copy_from_slice(b"abc");
+    ///
+    /// assert_eq!(machine.memory_slice(9, 5), b"\0abc\0");
+    /// ```
+    #[must_use]
+    pub fn memory_mut(&mut self, start: u16, len: u16) -> &mut [u8] {
+        let (start, end) = memory_range(start, len);
+        self.memory.get_mut(start..end).unwrap_or_default()
+    }
+
+    /// Fills `len` bytes of memory starting at `start` with `value`,
+    /// wrapping around to the start of memory (`memset` and `memfilla`).
+    fn fill_memory(&mut self, start: u16, value: u8, len: u16) {
+        for i in 0..len as usize {
+            #[allow(clippy::indexing_slicing)]
+            let slot = &mut self.memory[wrapping_address(start, i)];
+            *slot = value;
+        }
+    }
+
+    /// Returns the memory from `start` up to (but not including) `end`,
+    /// the region printed by `debugmemoryregion`.
+    ///
+    /// Returns `None` if `start` is after `end`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.load_bytes(b"abc", 10);
+    ///
+    /// assert_eq!(machine.debug_memory_region(10, 13), Some(&b"abc"[..]));
+    /// assert_eq!(machine.debug_memory_region(13, 10), None);
+    /// ```
+    #[must_use]
+    pub fn debug_memory_region(&self, start: u16, end: u16) -> Option<&[u8]> {
+        self.memory.get(start as usize..end as usize)
+    }
+
+    /// Returns the stack from `start` up to (but not including) `end`,
+    /// the region printed by `debugstackregion`.
+    ///
+    /// Returns `None` if `start` is after `end` or `end` is past the top of the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.stack.push_bytes(b"abc").unwrap();
+    ///
+    /// assert_eq!(machine.debug_stack_region(1, 3), Some(&b"bc"[..]));
+    /// assert_eq!(machine.debug_stack_region(3, 1), None);
+    /// assert_eq!(machine.debug_stack_region(0, 4), None);
+    /// ```
+    #[must_use]
+    pub fn debug_stack_region(&self, start: u16, end: u16) -> Option<&[u8]> {
+        self.stack.vec.get(start as usize..end as usize)
+    }
+
     /// Fetches a byte at [`reg_ep`] and increments [`reg_ep`] by 1.
     #[inline]
     #[allow(clippy::indexing_slicing)]
@@ -251,146 +704,50 @@ impl Machine {
 
     /// Prints [`num_reg`] with a colon and a space after it
     /// if [`reg_Ω.should_make_infinite_paperclips`] is enabled.
+    ///
+    /// Errors from writing to [`io`](Self::io) are ignored.
     pub fn num_debug(&self) {
-        if self.reg_Ω.should_make_infinite_paperclips {
-            print!("{}: ", self.num_reg);
-        }
+        _ = self.write_num_prefix();
+    }
+
+    /// Writes the [`num_debug`](Self::num_debug) prefix with [`write_output`](Self::write_output).
+    fn write_num_prefix(&self) -> std::io::Result<()> {
+        self.num_prefix()
+            .map_or(Ok(()), |prefix| self.write_output(prefix.as_bytes()))
+    }
+
+    /// Writes the [`num_debug`](Self::num_debug) prefix with [`write_debug`](Self::write_debug),
+    /// so that it ends up in the same place as the debug text after it.
+    fn write_debug_num_prefix(&self) -> std::io::Result<()> {
+        self.num_prefix()
+            .map_or(Ok(()), |prefix| self.write_debug(prefix.as_bytes()))
+    }
+
+    /// Returns the [`num_debug`](Self::num_debug) prefix if it should be printed.
+    fn num_prefix(&self) -> Option<String> {
+        self.reg_Ω
+            .should_make_infinite_paperclips
+            .then(|| format!("{}: ", self.num_reg))
     }
 
     /// Fetches an instruction from memory,
     /// incrementing [`reg_ep`] based on the amount of bytes read.
     ///
-    /// Returns `None` if the machine is halted.
+    /// Returns `None` if the machine is halted or if the instruction
+    /// is invalid (more info at [`Decoder::decode`]).
     pub fn fetch_instruction(&mut self) -> Option<Instruction> {
-        use {Instruction as I, InstructionKind as IK};
-
         if self.halted {
             return None;
         }
 
-        Some(match self.fetch_instruction_kind()? {
-            IK::Nop => I::Nop,
-
-            IK::Ldar => I::Ldar(self.fetch_2_bytes()),
-            IK::Sba => I::Sba,
-
-            IK::Clř => I::Clř,
-            IK::Dumpř => I::Dumpř(self.fetch_2_bytes()),
-            IK::Movař => I::Movař(self.fetch_byte()),
-            IK::Setř => I::Setř(self.fetch_byte(), self.fetch_2_bytes()),
-            IK::Setiř => I::Setiř(
-                self.fetch_byte(),
-                safe_transmute::<u8, i8, 1>(self.fetch_byte()),
-            ),
-            IK::Ldř => I::Ldř(self.fetch_2_bytes()),
-            IK::Ldiř => {
-                let mut array = [0; 37];
-
-                for item in &mut array {
-                    *item = safe_transmute::<u8, i8, 1>(self.fetch_byte());
-                }
-
-                I::Ldiř(array)
-            }
-
-            IK::Clß => I::Clß,
-            IK::Dumpß => I::Dumpß(self.fetch_2_bytes()),
-            IK::Writeß => I::Writeß(self.fetch_2_bytes(), self.fetch_byte()),
-            IK::Movaß => I::Movaß(self.fetch_byte()),
-            IK::Setß => I::Setß(self.fetch_2_bytes(), self.fetch_byte()),
-            IK::Setiß => I::Setiß(self.fetch_byte(), self.fetch_byte()),
-            IK::Ldß => I::Ldß(self.fetch_2_bytes()),
-            IK::Pushß => I::Pushß,
-            IK::Popß => I::Popß,
-            IK::Lenßa => I::Lenßa,
-            IK::Ldidp => I::Ldidp(self.fetch_2_bytes()),
-
-            #[allow(clippy::missing_transmute_annotations)]
-            // SAFETY: The VM machine code's author should guarantee that it is a valid enum variant expressed as a u8.
-            IK::ΩChoiceSet => I::ΩChoiceSet(unsafe { transmute(self.fetch_byte()) }),
-            IK::ΩChoiceGetA => I::ΩChoiceGetA,
-
-            IK::ΩGainAPolymorphicDesires => I::ΩGainAPolymorphicDesires,
-            IK::ΩLoseAPolymorphicDesires => I::ΩLoseAPolymorphicDesires,
-            IK::ΩPushPolymorphicDesires => I::ΩPushPolymorphicDesires,
-
-            IK::ΩTheEndIsNear => I::ΩTheEndIsNear,
-            IK::ΩSkipToTheChase => I::ΩSkipToTheChase,
-
-            IK::ΩSetSentience => I::ΩSetSentience(self.fetch_byte() != 0),
-            IK::ΩSetPaperclipProduction => I::ΩSetPaperclipProduction(self.fetch_byte() != 0),
-
-            IK::AddBL => I::AddBL,
-            IK::SubBL => I::SubBL,
-            IK::MulBL => I::MulBL,
-            IK::DivBL => I::DivBL,
-            IK::ModBL => I::ModBL,
-
-            IK::NotL => I::NotL,
-            IK::AndBL => I::AndBL,
-            IK::OrBL => I::OrBL,
-            IK::XorBL => I::XorBL,
-
-            IK::CmpLB => I::CmpLB,
-
-            IK::TgFlag => I::TgFlag,
-            IK::ClFlag => I::ClFlag,
-
-            IK::AddF => I::AddF(self.fetch_2_bytes()),
-            IK::SubF => I::SubF(self.fetch_2_bytes()),
-            IK::MulF => I::MulF(self.fetch_2_bytes()),
-            IK::DivF => I::DivF(self.fetch_2_bytes()),
-            IK::ModF => I::ModF(self.fetch_2_bytes()),
-
-            IK::StackAlloc => I::StackAlloc(self.fetch_2_bytes()),
-            IK::StackDealloc => I::StackDealloc(self.fetch_2_bytes()),
-
-            IK::Push => I::Push(self.fetch_2_bytes()),
-            IK::Pushi => I::Pushi(self.fetch_byte()),
-            IK::Pop => I::Pop(self.fetch_2_bytes()),
-
-            IK::Popa => I::Popa,
-            IK::Pusha => I::Pusha,
+        let mut decoder = Decoder::new(self.memory.as_slice(), self.reg_ep);
+        let instruction = decoder.decode();
+        self.reg_ep = decoder.position();
 
-            IK::Popb => I::Popb,
-            IK::Pushb => I::Pushb,
-
-            IK::PopL => I::PopL,
-            IK::PushL => I::PushL,
-
-            IK::Popf => I::Popf,
-            IK::Pushf => I::Pushf,
-
-            IK::Popch => I::Popch,
-            IK::Pushch => I::Pushch,
-
-            IK::Popnum => I::Popnum,
-            IK::Pushnum => I::Pushnum,
-
-            IK::Popep => I::Popep,
-            IK::Zpopep => I::Zpopep,
-            IK::Ppopep => I::Ppopep,
-            IK::Npopep => I::Npopep,
-            IK::Fpopep => I::Fpopep,
-            IK::Zapopep => I::Zapopep,
-            IK::Dpopep => I::Dpopep,
-
-            IK::GetChar => I::GetChar,
-            IK::GetLine => I::GetLine,
-
-            IK::WriteChar => I::WriteChar,
-            IK::WriteLineß => I::WriteLineß,
-            IK::WriteLine => I::WriteLine(self.fetch_2_bytes()),
-
-            IK::ToggleDebug => I::ToggleDebug,
-            IK::DebugMachineState => I::DebugMachineState,
-            IK::DebugMachineStateCompact => I::DebugMachineStateCompact,
-            IK::DebugMemoryRegion => {
-                I::DebugMemoryRegion(self.fetch_2_bytes(), self.fetch_2_bytes())
-            }
-            IK::DebugStackRegion => I::DebugStackRegion(self.fetch_2_bytes(), self.fetch_2_bytes()),
-            IK::ShowChoice => I::ShowChoice,
-        })
+        if let Some(instruction) = instruction {
+            self.count_opcode(instruction);
+        }
+        instruction
     }
     #[allow(
         clippy::too_many_lines,
@@ -407,6 +764,17 @@ impl Machine {
         #[allow(clippy::enum_glob_use)]
         use Instruction::*;
 
+        self.instructions_executed = self.instructions_executed.saturating_add(1);
+
+        if self.suppress_io && instruction.is_io() {
+            // `getchar` doesn't check the dot pointer and `writelineifflag` doesn't set the flag
+            let sets_flag_on_dot_pointer = !matches!(instruction, GetChar | WriteLineIfFlag(_));
+            if sets_flag_on_dot_pointer && self.memory.get(self.reg_dp as usize) != Some(&b'.') {
+                self.flag = true;
+            }
+            return;
+        }
+
         /// Tries to push or pop a value with a method and a value,
         /// setting the flag if it fails.
         macro_rules! try_stack {
@@ -435,6 +803,51 @@ impl Machine {
             Nop => (),
 
             Ldar(data) => self.reg_a = self.memory[data as usize],
+            LdarL => {
+                if let Some(&byte) = self.memory.get(self.reg_L as usize) {
+                    self.reg_a = byte;
+                } else {
+                    self.flag = true;
+                }
+            }
+            StarL => {
+                if let Some(byte) = self.memory.get_mut(self.reg_L as usize) {
+                    *byte = self.reg_a;
+                } else {
+                    self.flag = true;
+                }
+            }
+            MemFillA(data0, data1) => self.fill_memory(data0, self.reg_a, data1),
+            MemCpy(data0, data1, data2) => {
+                let len = data2 as usize;
+                let src = wrapping_address(data1, 0);
+                let dst = wrapping_address(data0, 0);
+
+                if src.saturating_add(len) <= MEMORY_SIZE && dst.saturating_add(len) <= MEMORY_SIZE
+                {
+                    self.memory.copy_within(src..src.saturating_add(len), dst);
+                } else {
+                    // a range wraps around the end of memory, so go through a buffer
+                    // to keep the `ptr::copy` semantics for overlapping ranges
+                    let mut bytes = vec![0; len];
+                    for (i, byte) in bytes.iter_mut().enumerate() {
+                        #[allow(clippy::indexing_slicing)]
+                        let slot = self.memory[wrapping_address(data1, i)];
+                        *byte = slot;
+                    }
+                    for (i, byte) in bytes.into_iter().enumerate() {
+                        #[allow(clippy::indexing_slicing)]
+                        let slot = &mut self.memory[wrapping_address(data0, i)];
+                        *slot = byte;
+                    }
+                }
+            }
+            MemSet(data0, data1, data2) => self.fill_memory(data0, data1, data2),
+            Shuffle(data0, data1) => {
+                if !self.shuffle_memory(data0, data1) {
+                    self.flag = true;
+                }
+            }
             Sba => {
                 self.reg_a = match self.reg_b {
                     ..=-1 => 255,
@@ -442,6 +855,28 @@ impl Machine {
                     1.. => 1,
                 }
             }
+            SwapAB => {
+                let [high, low] = self.reg_b.to_be_bytes();
+                self.reg_b = i16::from_be_bytes([high, self.reg_a]);
+                self.reg_a = low;
+            }
+            MovAB => self.reg_b = i16::from(self.reg_a),
+            MovBA => self.reg_a = self.reg_b.to_be_bytes()[1],
+            RotlA(data) => self.reg_a = self.reg_a.rotate_left(u32::from(data % 8)),
+            RotrA(data) => self.reg_a = self.reg_a.rotate_right(u32::from(data % 8)),
+            AToDec(data) => {
+                let digits = [self.reg_a / 100, self.reg_a / 10 % 10, self.reg_a % 10]
+                    .map(|d| b'0'.wrapping_add(d));
+
+                if let Some(memory) = self
+                    .memory
+                    .get_mut(data as usize..(data as usize).saturating_add(digits.len()))
+                {
+                    memory.copy_from_slice(&digits);
+                } else {
+                    self.flag = true;
+                }
+            }
 
             Clř => self.reg_ř = [0; 37],
             Dumpř(data) => {
@@ -456,8 +891,11 @@ impl Machine {
                 }
             }
             Setř(data0, data1) => {
-                if let Some(v) = self.reg_ř.get_mut(data0 as usize) {
-                    self.memory[data1 as usize] = safe_transmute::<i8, u8, 1>(*v);
+                if let (Some(v), Some(&byte)) = (
+                    self.reg_ř.get_mut(data0 as usize),
+                    self.memory.get(data1 as usize),
+                ) {
+                    *v = safe_transmute::<u8, i8, 1>(byte);
                 }
             }
             Setiř(data0, data1) => {
@@ -476,29 +914,20 @@ impl Machine {
 
             Clß => self.reg_ß.clear(),
             Dumpß(data) => {
-                for i in 0..self.reg_ß.len() {
-                    self.memory[data.wrapping_add(i as u16) as usize] =
-                        if let Some(v) = self.reg_ß.get(i) {
-                            v
-                        } else {
-                            self.flag = true;
-                            return;
-                        };
+                for (i, byte) in self.reg_ß.bytes().enumerate() {
+                    self.memory[data.wrapping_add(i as u16) as usize] = byte;
                 }
             }
             Writeß(data0, data1) => {
-                self.memory[data0 as usize] = if let Some(v) = self.reg_ß.get(data1 as usize) {
-                    v
-                } else {
+                let (Some(byte), Some(slot)) = (
+                    self.reg_ß.get(data1 as usize),
+                    self.memory.get_mut(data0 as usize),
+                ) else {
                     self.flag = true;
                     return;
                 };
-                self.reg_a = if let Some(v) = self.reg_ß.get(data1 as usize) {
-                    v
-                } else {
-                    self.flag = true;
-                    return;
-                }
+                *slot = byte;
+                self.reg_a = byte;
             }
             Movaß(data) => if self.reg_ß.set(data as usize, self.reg_a).is_err() {},
             Setß(data0, data1) => {
@@ -515,15 +944,13 @@ impl Machine {
             Ldß(data) => {
                 self.reg_ß.clear();
 
-                // SAFETY: The VM machine code's author should gurantee that the data is valid UTF-8.
-                if unsafe {
-                    self.reg_ß
-                        .push_bytes(&self.memory[data as usize..data.saturating_add(255) as usize])
-                }
-                .is_err()
-                {
+                let bytes = &self.memory[data as usize..data.saturating_add(255) as usize];
+                let valid = std::str::from_utf8(bytes).is_ok();
+
+                // SAFETY: `bytes` is only pushed if it's valid UTF-8
+                if !valid || unsafe { self.reg_ß.push_bytes(bytes) }.is_err() {
                     self.flag = true;
-                };
+                }
             }
             Pushß => match self
                 .stack
@@ -539,14 +966,26 @@ impl Machine {
                 _ => self.flag = true,
             },
             Lenßa => self.reg_a = self.reg_ß.len() as u8,
+            Lenßl => self.reg_L = u16::try_from(self.reg_ß.len()).unwrap_or(u16::MAX),
+            Jßeq(data0, data1) => match self.c_str_at(data0) {
+                Some(str) => {
+                    if str == self.reg_ß.as_bytes() {
+                        self.reg_ep = data1;
+                    }
+                }
+                None => self.flag = true,
+            },
 
             Ldidp(data) => {
                 if is_fib_prime_or_semiprime_u16(data) {
                     self.reg_dp = data;
                 } else {
-                    self.flag = false;
+                    self.flag = true;
                 }
             }
+            CheckDp => self.flag = self.memory[self.reg_dp as usize] != b'.',
+            Ldinum(data) => self.num_reg = data,
+            Ldif(data) => self.reg_f = f64::from_bits(data),
 
             ΩChoiceSet(data) => self.reg_Ω.illusion_of_choice = data,
             ΩChoiceGetA => self.reg_a = 0,
@@ -576,7 +1015,7 @@ impl Machine {
             ΩTheEndIsNear => self.reg_Ω.feeling_of_impending_doom = true,
             ΩSkipToTheChase => {
                 if self.reg_Ω.feeling_of_impending_doom {
-                    self.halted = true;
+                    self.halt(HaltReason::Doom);
                 }
             }
 
@@ -592,30 +1031,24 @@ impl Machine {
                 self.reg_Ω.should_make_infinite_paperclips = enable;
             }
 
-            AddBL => {
-                (self.reg_L, self.flag) = self.reg_L.overflowing_add(safe_transmute(self.reg_b));
-            }
-            SubBL => {
-                (self.reg_L, self.flag) = self.reg_L.overflowing_sub(safe_transmute(self.reg_b));
-            }
-            MulBL => {
-                (self.reg_L, self.flag) = self.reg_L.overflowing_mul(safe_transmute(self.reg_b));
-            }
-            DivBL => {
-                (self.reg_L, self.flag) = self.reg_L.overflowing_div(safe_transmute(self.reg_b));
-            }
-            ModBL => {
-                self.reg_L = self
-                    .reg_L
-                    .checked_rem(safe_transmute::<i16, u16, 2>(self.reg_b))
-                    .unwrap_or(0);
-            }
+            AddBL => self.arithmetic(u16::overflowing_add),
+            SubBL => self.arithmetic(u16::overflowing_sub),
+            AddiL(data) => self.arithmetic(|l, _| l.overflowing_add_signed(data)),
+            SubiL(data) => self.arithmetic(|l, _| l.overflowing_sub_signed(data)),
+            IncL => self.arithmetic(|l, _| l.overflowing_add(1)),
+            DecL => self.arithmetic(|l, _| l.overflowing_sub(1)),
+            MulBL => self.arithmetic(u16::overflowing_mul),
+            DivBL => self.arithmetic(|l, b| l.checked_div(b).map_or((l, true), |q| (q, false))),
+            ModBL => self.arithmetic(|l, b| l.checked_rem(b).map_or((l, true), |r| (r, false))),
 
             NotL => self.reg_L = !self.reg_L,
 
             AndBL => self.reg_L &= safe_transmute::<i16, u16, 2>(self.reg_b),
             OrBL => self.reg_L |= safe_transmute::<i16, u16, 2>(self.reg_b),
             XorBL => self.reg_L ^= safe_transmute::<i16, u16, 2>(self.reg_b),
+            PopcntL => self.reg_a = self.reg_L.count_ones() as u8,
+            CtzL => self.reg_a = self.reg_L.trailing_zeros() as u8,
+            ClzL => self.reg_a = self.reg_L.leading_zeros() as u8,
 
             CmpLB => {
                 if self.reg_L > i16::MAX as u16 {
@@ -632,9 +1065,26 @@ impl Machine {
                     self.flag = true;
                 }
             }
+            ClampL => {
+                if self.reg_L > i16::MAX as u16 {
+                    self.reg_L = i16::MAX as u16;
+                    self.flag = true;
+                }
+            }
+            CmpEqLB => self.flag = i32::from(self.reg_L) == i32::from(self.reg_b),
+            CmpLtLB => self.flag = i32::from(self.reg_L) < i32::from(self.reg_b),
+            CmpGtLB => self.flag = i32::from(self.reg_L) > i32::from(self.reg_b),
 
             TgFlag => self.flag = !self.flag,
             ClFlag => self.flag = false,
+            PushFlagClear => {
+                let flag = self.flag;
+                self.flag = false;
+                try_stack!(push self.stack => push_byte, u8::from(flag), self.flag => true);
+            }
+            PopFlag => {
+                try_stack!(pop self.stack => pop_byte, fn |v| self.flag = v != 0, self.flag => true);
+            }
 
             AddF(data) => {
                 self.reg_f +=
@@ -656,6 +1106,30 @@ impl Machine {
                 self.reg_f %=
                     safe_transmute::<u64, f64, 8>(index_u64(self.memory.as_slice(), data));
             }
+            SqrtF => {
+                if self.reg_f < 0.0 {
+                    self.flag = true;
+                }
+                self.reg_f = self.reg_f.sqrt();
+            }
+            FloorF => self.reg_f = self.reg_f.floor(),
+            CeilF => self.reg_f = self.reg_f.ceil(),
+            RoundF => self.reg_f = self.reg_f.round(),
+            FtoL => {
+                if self.reg_f.is_finite() {
+                    let truncated = self.reg_f.trunc();
+                    if !(0.0..=f64::from(u16::MAX)).contains(&truncated) {
+                        self.flag = true;
+                    }
+                    // `as` saturates, which is the clamping
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let clamped = truncated as u16;
+                    self.reg_L = clamped;
+                } else {
+                    self.flag = true;
+                }
+            }
+            LtoF => self.reg_f = f64::from(self.reg_L),
 
             StackAlloc(amount) => {
                 if self.stack.alloc(amount as usize).is_err() {
@@ -668,6 +1142,23 @@ impl Machine {
                     self.flag = true;
                 }
             }
+            StackDup => match self.stack.peek_byte() {
+                Some(byte) => {
+                    if self.stack.push_byte(byte).is_err() {
+                        self.flag = true;
+                    }
+                }
+                None => self.flag = true,
+            },
+            StackSwap => {
+                let start = self.stack.used_space().checked_sub(2);
+
+                match start.and_then(|start| self.stack.vec.get_mut(start..)) {
+                    Some(top) => top.reverse(),
+                    None => self.flag = true,
+                }
+            }
+            StackClear => self.stack.clear(),
 
             Push(data) => {
                 if self.stack.push_byte(self.memory[data as usize]).is_err() {
@@ -679,9 +1170,35 @@ impl Machine {
                     self.flag = true;
                 }
             }
+            PushiU16(data) => {
+                if self.stack.push_bytes(&data.to_be_bytes()).is_err() {
+                    self.flag = true;
+                }
+            }
             Pop(data) => {
                 try_stack!(pop self.stack => pop_byte, self.memory[data as usize], self.flag => true);
             }
+            PopMem(data0, data1) => 'block: {
+                let len = data1 as usize;
+                let Some(stack_start) = self.stack.used_space().checked_sub(len) else {
+                    self.flag = true;
+                    break 'block;
+                };
+                let Some(end) = (data0 as usize)
+                    .checked_add(len)
+                    .filter(|&end| end <= self.memory.len())
+                else {
+                    self.flag = true;
+                    break 'block;
+                };
+
+                self.memory[data0 as usize..end].copy_from_slice(&self.stack.vec[stack_start..]);
+
+                // SAFETY: the popped bytes were copied above and aren't used afterwards
+                if unsafe { self.stack.dealloc(len) }.is_err() {
+                    self.flag = true;
+                }
+            }
 
             Popa => {
                 try_stack!(pop self.stack => pop_byte, self.reg_a, self.flag => true);
@@ -689,37 +1206,44 @@ impl Machine {
             Pusha => try_stack!(push self.stack => push_byte, self.reg_a, self.flag => true),
 
             Popb => {
-                try_stack!(pop self.stack => pop_u16, fn |v| self.reg_b = safe_transmute(v), self.flag => true);
+                try_stack!(pop self.stack => pop_i16, self.reg_b, self.flag => true);
             }
             Pushb => {
-                try_stack!(push self.stack => push_bytes, &self.reg_b.to_be_bytes(), self.flag => true);
+                try_stack!(push self.stack => push_i16, self.reg_b, self.flag => true);
             }
 
             PopL => try_stack!(pop self.stack => pop_u16, self.reg_L, self.flag => true),
             PushL => {
                 try_stack!(push self.stack => push_bytes, &self.reg_L.to_be_bytes(), self.flag => true);
             }
+            SpToL => match u16::try_from(self.stack.used_space()) {
+                Ok(depth) => self.reg_L = depth,
+                Err(_) => self.flag = true,
+            },
+            EpToL => self.reg_L = self.reg_ep,
 
             Popf => {
-                try_stack!(pop self.stack => pop_u64, fn |v| self.reg_f = safe_transmute(v), self.flag => true);
+                try_stack!(pop self.stack => pop_f64, self.reg_f, self.flag => true);
             }
             Pushf => {
-                try_stack!(push self.stack => push_bytes, &self.reg_f.to_be_bytes(), self.flag => true);
+                try_stack!(push self.stack => push_f64, self.reg_f, self.flag => true);
             }
 
             Popch => {
-                // SAFETY: The VM machine code's author should gurantee that it's a valid character
-                try_stack!(pop self.stack => pop_u32, fn |v| self.reg_ch = unsafe { char::from_u32_unchecked(v) }, self.flag => true);
+                try_stack!(pop self.stack => pop_u32, fn |v| match char::from_u32(v) {
+                    Some(ch) => self.reg_ch = ch,
+                    None => self.flag = true,
+                }, self.flag => true);
             }
             Pushch => {
                 try_stack!(push self.stack => push_bytes, &(self.reg_ch as u32).to_be_bytes(), self.flag => true);
             }
 
             Popnum => {
-                try_stack!(pop self.stack => pop_u32, fn |v| self.num_reg = safe_transmute(v), self.flag => true);
+                try_stack!(pop self.stack => pop_i32, self.num_reg, self.flag => true);
             }
             Pushnum => {
-                try_stack!(push self.stack => push_bytes, &self.num_reg.to_be_bytes(), self.flag => true);
+                try_stack!(push self.stack => push_i32, self.num_reg, self.flag => true);
             }
 
             Popep => {
@@ -755,69 +1279,164 @@ impl Machine {
                     try_stack!(pop self.stack => pop_u16, fn |v| self.reg_ep = safe_transmute(v), self.flag => true);
                 }
             }
-
-            GetChar => 'block: {
-                use crossterm::{
-                    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-                    terminal::{disable_raw_mode, enable_raw_mode},
-                };
-
-                if enable_raw_mode().is_err() {
-                    self.flag = true;
-                    break 'block;
-                };
-
-                loop {
-                    match event::read() {
-                        Ok(Event::Key(KeyEvent {
-                            code: KeyCode::Char(c),
-                            kind: KeyEventKind::Press,
-                            ..
-                        })) => {
-                            self.reg_ch = c;
-                            break;
-                        }
-                        Err(_) => self.flag = true,
-                        _ => (),
-                    }
+            JmpL => self.reg_ep = self.reg_L,
+            Jmp(data) => self.reg_ep = self.reg_ep.wrapping_add_signed(data),
+            Jz(data) => {
+                if self.reg_b == 0 {
+                    self.reg_ep = self.reg_ep.wrapping_add_signed(data);
                 }
-
-                if disable_raw_mode().is_err() {
-                    self.flag = true;
-                };
             }
-
-            GetLine => 'block: {
+            Jnz(data) => {
+                if self.reg_b != 0 {
+                    self.reg_ep = self.reg_ep.wrapping_add_signed(data);
+                }
+            }
+            Jf(data) => {
+                if self.flag {
+                    self.reg_ep = self.reg_ep.wrapping_add_signed(data);
+                }
+            }
+            FjmpClear(data) => {
+                if self.flag {
+                    self.flag = false;
+                    self.reg_ep = data;
+                }
+            }
+            Call(data) => {
+                if self.stack.push_bytes(&self.reg_ep.to_be_bytes()).is_ok() {
+                    self.reg_ep = data;
+                } else {
+                    self.flag = true;
+                }
+            }
+            Ret => {
+                try_stack!(pop self.stack => pop_u16, fn |v| self.reg_ep = v, self.flag => true);
+            }
+            Halt => self.halt(HaltReason::Halt),
+            BeqAi(data0, data1) => {
+                if self.reg_a == data0 {
+                    self.reg_ep = data1;
+                }
+            }
+            JmemEq(data0, data1, data2) => {
+                if self.memory.get(data0 as usize) == Some(&data1) {
+                    self.reg_ep = data2;
+                }
+            }
+
+            GetChar => {
+                let c = lock(&self.io, "IO").ok().and_then(|mut io| io.read_char());
+
+                match c {
+                    Some(c) => self.reg_ch = c,
+                    None => self.flag = true,
+                }
+            }
+
+            GetLine => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
                     self.flag = true;
                     break 'block;
                 }
 
-                let mut buf = String::with_capacity(255);
-                if std::io::stdin().take(255).read_to_string(&mut buf).is_err() {
-                    self.flag = true;
+                self.reg_ß.clear();
 
+                let line = lock(&self.io, "IO")
+                    .ok()
+                    .and_then(|mut io| io.read_line(255));
+
+                match line {
+                    Some(line) => {
+                        // SAFETY: a `String` is valid UTF-8
+                        if unsafe { self.reg_ß.push_bytes(line.as_bytes()) }.is_err() {
+                            self.flag = true;
+                        }
+                    }
+                    None => self.flag = true,
+                }
+            }
+            ReadNum => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
+                    self.flag = true;
                     break 'block;
                 }
+
+                let number = lock(&self.io, "IO")
+                    .ok()
+                    .and_then(|mut io| io.read_line(255))
+                    .and_then(|line| line.trim().parse().ok());
+
+                match number {
+                    Some(number) => self.num_reg = number,
+                    None => self.flag = true,
+                }
             }
+            GetByte => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
+                    self.flag = true;
+                    break 'block;
+                }
 
-            WriteChar => 'block: {
+                let mut byte = [0];
+                match self.read_input(&mut byte) {
+                    Ok(1) => self.reg_a = byte[0],
+                    Ok(_) | Err(_) => self.flag = true,
+                }
+            }
+            AtEof => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
                     self.flag = true;
                     break 'block;
                 }
 
-                self.num_debug();
+                self.flag = !self.peek_input();
+            }
 
-                let mut stdout = std::io::stdout();
+            WriteChar => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
+                    self.flag = true;
+                    break 'block;
+                }
 
                 let buf: &mut [u8; 4] = &mut [0, 0, 0, 0];
-                self.reg_ch.encode_utf8(buf);
+                let bytes = self.reg_ch.encode_utf8(buf).as_bytes();
 
-                if stdout.write_all(buf).is_err() {
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(bytes))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
+            }
+            PutByte => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
+                    self.flag = true;
+                    break 'block;
+                }
+
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(&[self.reg_a]))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
+            }
+            WriteNum => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
                     self.flag = true;
                     break 'block;
                 }
+
+                let text = self.num_reg.to_string();
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
 
             WriteLineß => 'block: {
@@ -826,8 +1445,14 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                print!("{}", self.reg_ß);
+                let text = self.reg_ß.to_string();
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
             WriteLine(data) => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
@@ -835,15 +1460,52 @@ impl Machine {
                     break 'block;
                 }
 
-                #[allow(clippy::multiple_unsafe_ops_per_block)]
-                // SAFETY: The VM machine code's author should guarantee that this doesn't lead to errors
-                let str = unsafe {
-                    std::ffi::CStr::from_ptr(self.memory.as_ptr().cast::<i8>().add(data as usize))
+                let Some(str) = self.c_str_at(data) else {
+                    self.flag = true;
+                    break 'block;
+                };
+                let str = String::from_utf8_lossy(str);
+
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(str.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
                 }
-                .to_string_lossy();
+            }
+            WriteLineIfFlag(data) => 'block: {
+                if !self.flag || self.memory[self.reg_dp as usize] != b'.' {
+                    break 'block;
+                }
+
+                let Some(str) = self.c_str_at(data) else {
+                    break 'block;
+                };
+                let str = String::from_utf8_lossy(str);
 
-                self.num_debug();
-                print!("{str}");
+                // the flag is already set, so errors writing don't change anything
+                let _ = self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(str.as_bytes()));
+            }
+            WriteStack(data0, data1) => 'block: {
+                if self.memory[self.reg_dp as usize] != b'.' {
+                    self.flag = true;
+                    break 'block;
+                }
+
+                let len = self.stack.vec.len();
+                let start = (data0 as usize).min(len);
+                let end = start.saturating_add(data1 as usize).min(len);
+
+                if self
+                    .write_num_prefix()
+                    .and_then(|()| self.write_output(&self.stack.vec[start..end]))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
 
             ToggleDebug => self.debug_mode = !self.debug_mode,
@@ -854,8 +1516,14 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                print!("{self:#?}");
+                let text = format!("{self:#?}");
+                if self
+                    .write_debug_num_prefix()
+                    .and_then(|()| self.write_debug(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
             DebugMachineStateCompact => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
@@ -863,8 +1531,14 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                print!("{self:?}");
+                let text = format!("{self:?}");
+                if self
+                    .write_debug_num_prefix()
+                    .and_then(|()| self.write_debug(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
 
             DebugMemoryRegion(data0, data1) => 'block: {
@@ -873,8 +1547,18 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                print!("{:?}", &self.memory[(data0 as usize)..(data1 as usize)]);
+                let Some(region) = self.debug_memory_region(data0, data1) else {
+                    self.flag = true;
+                    break 'block;
+                };
+                let text = format!("{region:?}");
+                if self
+                    .write_debug_num_prefix()
+                    .and_then(|()| self.write_debug(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
             DebugStackRegion(data0, data1) => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
@@ -882,8 +1566,18 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                print!("{:?}", &self.stack.vec[(data0 as usize)..(data1 as usize)]);
+                let Some(region) = self.debug_stack_region(data0, data1) else {
+                    self.flag = true;
+                    break 'block;
+                };
+                let text = format!("{region:?}");
+                if self
+                    .write_debug_num_prefix()
+                    .and_then(|()| self.write_debug(text.as_bytes()))
+                    .is_err()
+                {
+                    self.flag = true;
+                }
             }
             ShowChoice => 'block: {
                 if self.memory[self.reg_dp as usize] != b'.' {
@@ -891,11 +1585,12 @@ impl Machine {
                     break 'block;
                 }
 
-                self.num_debug();
-                if self
-                    .reg_Ω
-                    .display_illusion_of_choice(&mut std::io::stdout())
-                    .is_err()
+                let mut text = Vec::new();
+                if self.reg_Ω.display_illusion_of_choice(&mut text).is_err()
+                    || self
+                        .write_num_prefix()
+                        .and_then(|()| self.write_output(&text))
+                        .is_err()
                 {
                     self.flag = true;
                 }
@@ -919,27 +1614,148 @@ impl Machine {
     /// at the specified offset.
     ///
     /// Returns the amount of bytes written
-    pub fn load(&mut self, data: &[DataOrInstruction], mut offset: u16) -> u16 {
-        let last_idx = &mut offset;
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.memory[1..8].fill(0xFF);
+    ///
+    /// let end = machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: byte 1;
+    ///         1: align 8;
+    ///         8: byte 2;
+    ///         9: align 9; // already aligned
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(end, 9);
+    /// assert_eq!(machine.memory[..9], [1, 0, 0, 0, 0, 0, 0, 0, 2]);
+    /// ```
+    pub fn load(&mut self, data: &[DataOrInstruction], offset: u16) -> u16 {
+        self.try_load(data, offset).unwrap_or_else(|_| {
+            // wraps around to the start of memory like `load_iter`
+            let mut offset = offset;
+            for item in data {
+                self.load_item(item, &mut offset);
+            }
+            offset
+        })
+    }
+    /// Loads data into the machine's memory
+    /// at the specified offset, like [`load`](Self::load).
+    ///
+    /// Returns the offset after the last item.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoadError::Overflow`] without writing anything
+    /// if an item would go past the end of memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::LoadError, Machine};
+    /// let mut machine = Machine::default();
+    ///
+    /// let program = esoteric_assembly! {
+    ///     pushi 1;
+    ///     popa;
+    /// };
+    ///
+    /// assert_eq!(machine.try_load(&program, 0xFFFC), Ok(0xFFFF));
+    /// assert_eq!(
+    ///     machine.try_load(&program, 0xFFFD),
+    ///     Err(LoadError::Overflow { at: 0xFFFF })
+    /// );
+    /// ```
+    pub fn try_load(&mut self, data: &[DataOrInstruction], offset: u16) -> Result<u16, LoadError> {
+        let mut end = offset;
+        for item in data {
+            end = end
+                .checked_add(item.loaded_len(end))
+                .filter(|&end| end as usize <= MEMORY_SIZE)
+                .ok_or(LoadError::Overflow { at: end })?;
+        }
 
-        for instruction in data {
-            match instruction {
-                DataOrInstruction::Instruction(instruction) => {
-                    self.load_instruction(*instruction, last_idx);
-                }
-                DataOrInstruction::Data(bytes) => {
-                    if let Some(v) = self.load_bytes(bytes, *last_idx) {
-                        *last_idx = v;
-                    }
+        let mut offset = offset;
+        for item in data {
+            self.load_item(item, &mut offset);
+        }
+        Ok(offset)
+    }
+    /// Loads data from an iterator into the machine's memory
+    /// at the specified offset, without collecting it first.
+    ///
+    /// Like [`load`](Self::load), it returns the offset after the last item.
+    /// Instructions and bytes that go past the end of memory wrap around
+    /// to the start, and data slices that don't fit are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{instruction::{DataOrInstruction, Instruction}, Machine};
+    /// let mut machine = Machine::default();
+    ///
+    /// let end = machine.load_iter(
+    ///     (0..3)
+    ///         .map(Instruction::Pushi)
+    ///         .chain([Instruction::ΩTheEndIsNear, Instruction::ΩSkipToTheChase])
+    ///         .map(DataOrInstruction::Instruction),
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(end, 8);
+    /// assert_eq!(machine.run(), 0);
+    /// assert_eq!(machine.stack.used_space(), 3);
+    /// ```
+    pub fn load_iter<'a>(
+        &mut self,
+        items: impl IntoIterator<Item = DataOrInstruction<'a>>,
+        mut offset: u16,
+    ) -> u16 {
+        for item in items {
+            self.load_item(&item, &mut offset);
+        }
+        offset
+    }
+    /// Loads a single item at `offset` and moves `offset` past it.
+    fn load_item(&mut self, item: &DataOrInstruction, offset: &mut u16) {
+        match item {
+            DataOrInstruction::Instruction(instruction) => {
+                self.load_instruction(*instruction, offset);
+            }
+            DataOrInstruction::Data(bytes) => {
+                if let Some(v) = self.load_bytes(bytes, *offset) {
+                    *offset = v;
                 }
-                #[allow(clippy::indexing_slicing)]
-                DataOrInstruction::ByteData(val) => {
-                    self.memory[*last_idx as usize] = *val;
-                    *last_idx = last_idx.wrapping_add(1);
+            }
+            #[allow(clippy::indexing_slicing)]
+            DataOrInstruction::ByteData(val) => {
+                self.memory[*offset as usize] = *val;
+                *offset = offset.wrapping_add(1);
+            }
+            DataOrInstruction::Zeroed(amount) => self.load_fill(0, *amount, offset),
+            DataOrInstruction::Fill(value, amount) => self.load_fill(*value, *amount, offset),
+            DataOrInstruction::PushString(bytes) => {
+                for &byte in *bytes {
+                    self.load_instruction(Instruction::Pushi(byte), offset);
                 }
             }
+            DataOrInstruction::Align(_) => self.load_fill(0, item.loaded_len(*offset), offset),
+        }
+    }
+    /// Loads `amount` copies of `value` at `offset` and moves `offset` past them.
+    fn load_fill(&mut self, value: u8, amount: u16, offset: &mut u16) {
+        for _ in 0..amount {
+            if let Some(byte) = self.memory.get_mut(*offset as usize) {
+                *byte = value;
+            }
+            *offset = offset.wrapping_add(1);
         }
-        *last_idx
     }
     /// Load bytes into the machine
     /// at the specified offset.
@@ -989,6 +1805,8 @@ impl Machine {
         #[allow(clippy::enum_glob_use)]
         use Instruction::*;
         use InstructionKind as IK;
+
+        let start = *offset;
         match instruction {
             Nop => load_byte(self.memory.as_mut_slice(), offset, IK::Nop as u8),
 
@@ -996,7 +1814,46 @@ impl Machine {
                 load_byte(self.memory.as_mut_slice(), offset, IK::Ldar as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            LdarL => load_byte(self.memory.as_mut_slice(), offset, IK::LdarL as u8),
+            StarL => load_byte(self.memory.as_mut_slice(), offset, IK::StarL as u8),
+            MemFillA(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::MemFillA as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
+            MemCpy(data0, data1, data2) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::MemCpy as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data2.to_be_bytes());
+            }
+            MemSet(data0, data1, data2) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::MemSet as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_byte(self.memory.as_mut_slice(), offset, data1);
+                load_bytes(self.memory.as_mut_slice(), offset, &data2.to_be_bytes());
+            }
+            Shuffle(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Shuffle as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
             Sba => load_byte(self.memory.as_mut_slice(), offset, IK::Sba as u8),
+            SwapAB => load_byte(self.memory.as_mut_slice(), offset, IK::SwapAB as u8),
+            MovAB => load_byte(self.memory.as_mut_slice(), offset, IK::MovAB as u8),
+            MovBA => load_byte(self.memory.as_mut_slice(), offset, IK::MovBA as u8),
+            RotlA(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::RotlA as u8);
+                load_byte(self.memory.as_mut_slice(), offset, data);
+            }
+            RotrA(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::RotrA as u8);
+                load_byte(self.memory.as_mut_slice(), offset, data);
+            }
+            AToDec(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::AToDec as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
 
             Clř => load_byte(self.memory.as_mut_slice(), offset, IK::Clř as u8),
             Dumpř(data) => {
@@ -1026,7 +1883,7 @@ impl Machine {
                 // SAFETY: the type changes from a non-invalidatable type to another non-invalidatable type.
                 load_bytes(self.memory.as_mut_slice(), offset, unsafe {
                     #[allow(clippy::ref_as_ptr)]
-                    &*(&arr as *const [i8] as *const [u8])
+                    &*(&raw const arr).cast::<[u8; 37]>()
                 });
             }
 
@@ -1061,11 +1918,26 @@ impl Machine {
             Pushß => load_byte(self.memory.as_mut_slice(), offset, IK::Pushß as u8),
             Popß => load_byte(self.memory.as_mut_slice(), offset, IK::Popß as u8),
             Lenßa => load_byte(self.memory.as_mut_slice(), offset, IK::Lenßa as u8),
+            Lenßl => load_byte(self.memory.as_mut_slice(), offset, IK::Lenßl as u8),
+            Jßeq(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Jßeq as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
 
             Ldidp(data) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::Ldidp as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            CheckDp => load_byte(self.memory.as_mut_slice(), offset, IK::CheckDp as u8),
+            Ldinum(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Ldinum as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Ldif(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Ldif as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
 
             ΩChoiceSet(data) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::ΩChoiceSet as u8);
@@ -1119,6 +1991,16 @@ impl Machine {
 
             AddBL => load_byte(self.memory.as_mut_slice(), offset, IK::AddBL as u8),
             SubBL => load_byte(self.memory.as_mut_slice(), offset, IK::SubBL as u8),
+            AddiL(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::AddiL as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            SubiL(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::SubiL as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            IncL => load_byte(self.memory.as_mut_slice(), offset, IK::IncL as u8),
+            DecL => load_byte(self.memory.as_mut_slice(), offset, IK::DecL as u8),
             MulBL => load_byte(self.memory.as_mut_slice(), offset, IK::MulBL as u8),
             DivBL => load_byte(self.memory.as_mut_slice(), offset, IK::DivBL as u8),
             ModBL => load_byte(self.memory.as_mut_slice(), offset, IK::ModBL as u8),
@@ -1128,11 +2010,20 @@ impl Machine {
             AndBL => load_byte(self.memory.as_mut_slice(), offset, IK::AndBL as u8),
             OrBL => load_byte(self.memory.as_mut_slice(), offset, IK::OrBL as u8),
             XorBL => load_byte(self.memory.as_mut_slice(), offset, IK::XorBL as u8),
+            PopcntL => load_byte(self.memory.as_mut_slice(), offset, IK::PopcntL as u8),
+            CtzL => load_byte(self.memory.as_mut_slice(), offset, IK::CtzL as u8),
+            ClzL => load_byte(self.memory.as_mut_slice(), offset, IK::ClzL as u8),
 
             CmpLB => load_byte(self.memory.as_mut_slice(), offset, IK::CmpLB as u8),
+            ClampL => load_byte(self.memory.as_mut_slice(), offset, IK::ClampL as u8),
+            CmpEqLB => load_byte(self.memory.as_mut_slice(), offset, IK::CmpEqLB as u8),
+            CmpLtLB => load_byte(self.memory.as_mut_slice(), offset, IK::CmpLtLB as u8),
+            CmpGtLB => load_byte(self.memory.as_mut_slice(), offset, IK::CmpGtLB as u8),
 
             TgFlag => load_byte(self.memory.as_mut_slice(), offset, IK::TgFlag as u8),
             ClFlag => load_byte(self.memory.as_mut_slice(), offset, IK::ClFlag as u8),
+            PushFlagClear => load_byte(self.memory.as_mut_slice(), offset, IK::PushFlagClear as u8),
+            PopFlag => load_byte(self.memory.as_mut_slice(), offset, IK::PopFlag as u8),
 
             AddF(data) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::AddF as u8);
@@ -1154,6 +2045,12 @@ impl Machine {
                 load_byte(self.memory.as_mut_slice(), offset, IK::ModF as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            SqrtF => load_byte(self.memory.as_mut_slice(), offset, IK::SqrtF as u8),
+            FloorF => load_byte(self.memory.as_mut_slice(), offset, IK::FloorF as u8),
+            CeilF => load_byte(self.memory.as_mut_slice(), offset, IK::CeilF as u8),
+            RoundF => load_byte(self.memory.as_mut_slice(), offset, IK::RoundF as u8),
+            FtoL => load_byte(self.memory.as_mut_slice(), offset, IK::FtoL as u8),
+            LtoF => load_byte(self.memory.as_mut_slice(), offset, IK::LtoF as u8),
 
             StackAlloc(amount) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::StackAlloc as u8);
@@ -1163,6 +2060,9 @@ impl Machine {
                 load_byte(self.memory.as_mut_slice(), offset, IK::StackDealloc as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &amount.to_be_bytes());
             }
+            StackDup => load_byte(self.memory.as_mut_slice(), offset, IK::StackDup as u8),
+            StackSwap => load_byte(self.memory.as_mut_slice(), offset, IK::StackSwap as u8),
+            StackClear => load_byte(self.memory.as_mut_slice(), offset, IK::StackClear as u8),
 
             Push(data) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::Push as u8);
@@ -1172,10 +2072,19 @@ impl Machine {
                 load_byte(self.memory.as_mut_slice(), offset, IK::Pushi as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            PushiU16(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::PushiU16 as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
             Pop(data) => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::Pop as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            PopMem(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::PopMem as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
 
             Popa => load_byte(self.memory.as_mut_slice(), offset, IK::Popa as u8),
             Pusha => load_byte(self.memory.as_mut_slice(), offset, IK::Pusha as u8),
@@ -1185,6 +2094,8 @@ impl Machine {
 
             PopL => load_byte(self.memory.as_mut_slice(), offset, IK::PopL as u8),
             PushL => load_byte(self.memory.as_mut_slice(), offset, IK::PushL as u8),
+            SpToL => load_byte(self.memory.as_mut_slice(), offset, IK::SpToL as u8),
+            EpToL => load_byte(self.memory.as_mut_slice(), offset, IK::EpToL as u8),
 
             Popf => load_byte(self.memory.as_mut_slice(), offset, IK::Popf as u8),
             Pushf => load_byte(self.memory.as_mut_slice(), offset, IK::Pushf as u8),
@@ -1202,12 +2113,55 @@ impl Machine {
             Fpopep => load_byte(self.memory.as_mut_slice(), offset, IK::Fpopep as u8),
             Zapopep => load_byte(self.memory.as_mut_slice(), offset, IK::Zapopep as u8),
             Dpopep => load_byte(self.memory.as_mut_slice(), offset, IK::Dpopep as u8),
+            JmpL => load_byte(self.memory.as_mut_slice(), offset, IK::JmpL as u8),
+            Jmp(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Jmp as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Jz(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Jz as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Jnz(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Jnz as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Jf(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Jf as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            FjmpClear(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::FjmpClear as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Call(data) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::Call as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            Ret => load_byte(self.memory.as_mut_slice(), offset, IK::Ret as u8),
+            Halt => load_byte(self.memory.as_mut_slice(), offset, IK::Halt as u8),
+            BeqAi(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::BeqAi as u8);
+                load_byte(self.memory.as_mut_slice(), offset, data0);
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
+            JmemEq(data0, data1, data2) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::JmemEq as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_byte(self.memory.as_mut_slice(), offset, data1);
+                load_bytes(self.memory.as_mut_slice(), offset, &data2.to_be_bytes());
+            }
 
             GetChar => load_byte(self.memory.as_mut_slice(), offset, IK::GetChar as u8),
 
             GetLine => load_byte(self.memory.as_mut_slice(), offset, IK::GetLine as u8),
+            ReadNum => load_byte(self.memory.as_mut_slice(), offset, IK::ReadNum as u8),
+            GetByte => load_byte(self.memory.as_mut_slice(), offset, IK::GetByte as u8),
+            AtEof => load_byte(self.memory.as_mut_slice(), offset, IK::AtEof as u8),
 
             WriteChar => load_byte(self.memory.as_mut_slice(), offset, IK::WriteChar as u8),
+            PutByte => load_byte(self.memory.as_mut_slice(), offset, IK::PutByte as u8),
+            WriteNum => load_byte(self.memory.as_mut_slice(), offset, IK::WriteNum as u8),
 
             WriteLineß => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::WriteLineß as u8);
@@ -1217,6 +2171,19 @@ impl Machine {
                 load_byte(self.memory.as_mut_slice(), offset, IK::WriteLine as u8);
                 load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
             }
+            WriteLineIfFlag(data) => {
+                load_byte(
+                    self.memory.as_mut_slice(),
+                    offset,
+                    IK::WriteLineIfFlag as u8,
+                );
+                load_bytes(self.memory.as_mut_slice(), offset, &data.to_be_bytes());
+            }
+            WriteStack(data0, data1) => {
+                load_byte(self.memory.as_mut_slice(), offset, IK::WriteStack as u8);
+                load_bytes(self.memory.as_mut_slice(), offset, &data0.to_be_bytes());
+                load_bytes(self.memory.as_mut_slice(), offset, &data1.to_be_bytes());
+            }
 
             ToggleDebug => {
                 load_byte(self.memory.as_mut_slice(), offset, IK::ToggleDebug as u8);
@@ -1252,6 +2219,156 @@ impl Machine {
             }
             ShowChoice => load_byte(self.memory.as_mut_slice(), offset, IK::ShowChoice as u8),
         }
+
+        debug_assert_eq!(
+            offset.wrapping_sub(start),
+            instruction.encoded_len(),
+            "{instruction:?} was loaded with the wrong length"
+        );
+    }
+
+    /// Fetches and executes one instruction.
+    ///
+    /// Records an undo record if the [`journal`](Self::journal) is enabled
+    /// and counts the instruction if it sets the flag and
+    /// [`flag_stats`](Self::flag_stats) is enabled.
+    ///
+    /// Does nothing if the machine is halted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instruction at [`reg_ep`](Self::reg_ep) is invalid.
+    pub fn step(&mut self) {
+        #[allow(clippy::expect_used)]
+        self.try_step().expect(INVALID_OPCODE_MESSAGE);
+    }
+
+    /// Fetches and executes one instruction like [`step`](Self::step),
+    /// but returns an error instead of panicking if the instruction is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunError::InvalidOpcode`] without changing anything
+    /// if the instruction at [`reg_ep`](Self::reg_ep) is invalid.
+    pub fn try_step(&mut self) -> Result<(), RunError> {
+        self.step_instruction().map(drop)
+    }
+
+    /// Fetches and executes one instruction like [`try_step`](Self::try_step)
+    /// and returns it, or [`None`] if the machine is halted.
+    pub(crate) fn step_instruction(&mut self) -> Result<Option<Instruction>, RunError> {
+        if self.halted {
+            return Ok(None);
+        }
+
+        let flag = self.flag;
+        let traced = self.trace.is_some().then(|| Traced::of(self));
+
+        let instruction = if self.journal.is_some() {
+            self.fetch_and_execute_recorded()?
+        } else {
+            self.fetch_and_execute()?
+        };
+
+        if let Some(traced) = traced {
+            self.write_trace(traced, instruction);
+        }
+
+        if !flag && self.flag {
+            if let Some(stats) = &mut self.flag_stats {
+                stats.record(instruction);
+            }
+        }
+
+        Ok(Some(instruction))
+    }
+
+    /// Fetches and executes one instruction,
+    /// leaving [`reg_ep`](Self::reg_ep) unchanged if it's invalid.
+    fn fetch_and_execute(&mut self) -> Result<Instruction, RunError> {
+        let at = self.reg_ep;
+        let Some(instruction) = self.fetch_instruction() else {
+            self.reg_ep = at;
+            return Err(RunError::InvalidOpcode {
+                opcode: self.memory.get(at as usize).copied().unwrap_or_default(),
+                at,
+            });
+        };
+        self.execute_instruction(instruction);
+        Ok(instruction)
+    }
+
+    /// Resets the machine so the program in memory can run again,
+    /// without reallocating anything.
+    ///
+    /// This clears the registers (including [`reg_Ω`](Self::reg_Ω),
+    /// [`reg_ep`](Self::reg_ep) and [`reg_dp`](Self::reg_dp)), the stack,
+    /// the flag, [`halted`](Self::halted), [`rng_state`](Self::rng_state)
+    /// and the records of the [`journal`](Self::journal).
+    ///
+    /// The memory and the settings ([`debug_mode`](Self::debug_mode),
+    /// [`arithmetic_mode`](Self::arithmetic_mode), [`io`](Self::io), [`flag_stats`](Self::flag_stats),
+    /// [`trace`](Self::trace) and the rest) are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 7;
+    ///         2: popa;
+    ///         3: Ωtheendisnear;
+    ///         4: Ωskiptothechase;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(machine.run(), 7);
+    /// machine.reset();
+    /// assert_eq!((machine.reg_a, machine.reg_ep, machine.halted), (0, 0, false));
+    /// assert_eq!(machine.run(), 7);
+    /// ```
+    pub fn reset(&mut self) {
+        self.reg_a = 0;
+        self.reg_b = 0;
+        self.reg_L = 0;
+        self.reg_f = 0.0;
+        self.reg_ch = '\0';
+        self.reg_ř = [0; 37];
+        self.reg_ß.clear();
+        self.reg_Ω = Ω::ZEROED;
+        self.num_reg = 0;
+        self.reg_ep = 0;
+        self.reg_dp = 0;
+        self.flag = false;
+        self.halted = false;
+        self.halted_by = None;
+        self.rng_state = 0;
+        self.stack.clear();
+
+        if let Some(journal) = &mut self.journal {
+            journal.clear();
+        }
+    }
+
+    /// Resets the machine like [`reset`](Self::reset) and also zeroes the memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.memory[1000] = 1;
+    /// machine.reg_a = 1;
+    ///
+    /// machine.reset_all();
+    /// assert_eq!((machine.memory[1000], machine.reg_a), (0, 0));
+    /// ```
+    pub fn reset_all(&mut self) {
+        self.reset();
+        self.memory.fill(0);
     }
 
     /// Runs the machine until it halts
@@ -1263,12 +2380,85 @@ impl Machine {
     /// with an esoteric message and an explaination for demistification.
     pub fn run(&mut self) -> u8 {
         while !self.halted {
-            let instruction = self.fetch_instruction();
-            #[allow(clippy::expect_used)]
-            self.execute_instruction(instruction.expect(
-                "EsotericVm.RuntimeException.FetchInstruction.NilInstruction.InvalidOpcode (bad instruction code)",
-            ));
+            self.step();
         }
         self.reg_a
     }
+
+    /// Runs the machine until it halts like [`run`](Self::run),
+    /// but returns an error instead of panicking if an instruction is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunError::InvalidOpcode`] if an invalid instruction is stumbled upon.
+    /// The machine is left as it was before the invalid instruction,
+    /// with [`reg_ep`](Self::reg_ep) pointing to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::RunError, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: nop;
+    ///         1: byte 0xFF;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(
+    ///     machine.try_run(),
+    ///     Err(RunError::InvalidOpcode { opcode: 0xFF, at: 1 })
+    /// );
+    /// assert_eq!(machine.reg_ep, 1);
+    /// ```
+    pub fn try_run(&mut self) -> Result<u8, RunError> {
+        while !self.halted {
+            self.try_step()?;
+        }
+        Ok(self.reg_a)
+    }
+
+    /// Runs the machine until it halts or `limit` instructions have been executed.
+    ///
+    /// Stopping at the limit leaves the machine exactly as it is between two steps,
+    /// so running it again continues where it left off as if it never stopped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an invalid opcode (instruction) is stumbled upon, like [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, machine::RunOutcome, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: nop;
+    ///         1: Ωtheendisnear;
+    ///         2: Ωskiptothechase;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// assert_eq!(machine.run_for(2), RunOutcome::LimitReached);
+    /// assert_eq!(machine.reg_ep, 2);
+    /// assert_eq!(machine.run_for(2), RunOutcome::Halted(0));
+    /// ```
+    pub fn run_for(&mut self, limit: u64) -> RunOutcome {
+        for _ in 0..limit {
+            if self.halted {
+                break;
+            }
+            self.step();
+        }
+
+        if self.halted {
+            RunOutcome::Halted(self.reg_a)
+        } else {
+            RunOutcome::LimitReached
+        }
+    }
 }
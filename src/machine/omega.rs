@@ -5,11 +5,13 @@
 use std::io::{self, Write};
 
 /// An esoteric type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ω {
     /// The illusion of choice.
     ///
     /// Highlights how ZSTs tend to be useless.
+    #[cfg_attr(feature = "serde", serde(with = "super::serialization::choice"))]
     pub illusion_of_choice: Option<Option<Option<Option<()>>>>,
 
     /// Polymorphic desires.
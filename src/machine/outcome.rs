@@ -0,0 +1,109 @@
+//! What executing an instruction did.
+//!
+//! More info at [`Machine::execute_observed`].
+
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use super::{
+    io::{lock, MachineIo},
+    Machine,
+};
+use crate::instruction::Instruction;
+
+/// What executing an instruction did.
+///
+/// Returned by [`Machine::execute_observed`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExecOutcome {
+    /// Whether the instruction halted the machine
+    pub halted: bool,
+    /// Whether the flag changed
+    pub flag_changed: bool,
+    /// Whether the execution pointer changed (a jump)
+    pub ep_changed: bool,
+    /// Whether anything was written to [`Machine::io`]
+    pub wrote_output: bool,
+}
+
+/// An IO backend that remembers if anything was written through it.
+struct Observed {
+    /// The backend that's written to and read from
+    inner: Arc<Mutex<dyn MachineIo + Send>>,
+    /// Whether anything was written
+    wrote: Arc<AtomicBool>,
+}
+
+impl MachineIo for Observed {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        lock(&self.inner, "IO")?.write(bytes)?;
+
+        if !bytes.is_empty() {
+            self.wrote.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        lock(&self.inner, "IO")?.read(buf)
+    }
+
+    fn at_eof(&mut self) -> bool {
+        lock(&self.inner, "IO").map_or(true, |mut io| io.at_eof())
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        lock(&self.inner, "IO").ok()?.read_char()
+    }
+
+    fn read_line(&mut self, max: usize) -> Option<String> {
+        lock(&self.inner, "IO").ok()?.read_line(max)
+    }
+}
+
+impl Machine {
+    /// Executes an instruction like [`execute_instruction`](Self::execute_instruction)
+    /// and reports what it did.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{instruction::Instruction, machine::outcome::ExecOutcome, Machine};
+    /// let mut machine = Machine::default();
+    ///
+    /// assert_eq!(
+    ///     machine.execute_observed(Instruction::Popa),
+    ///     ExecOutcome { flag_changed: true, ..ExecOutcome::default() }
+    /// );
+    /// assert_eq!(
+    ///     machine.execute_observed(Instruction::Nop),
+    ///     ExecOutcome::default()
+    /// );
+    /// ```
+    pub fn execute_observed(&mut self, instruction: Instruction) -> ExecOutcome {
+        let (halted, flag, ep) = (self.halted, self.flag, self.reg_ep);
+
+        let wrote = Arc::new(AtomicBool::new(false));
+        let io = Arc::clone(&self.io);
+        self.io = Arc::new(Mutex::new(Observed {
+            inner: Arc::clone(&io),
+            wrote: Arc::clone(&wrote),
+        }));
+
+        self.execute_instruction(instruction);
+        self.io = io;
+
+        ExecOutcome {
+            halted: !halted && self.halted,
+            flag_changed: flag != self.flag,
+            ep_changed: ep != self.reg_ep,
+            wrote_output: wrote.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Taking a machine apart without copying its buffers.
+//!
+//! More info at [`Machine::into_parts`].
+
+use std::{
+    fmt::{self, Debug},
+    sync::{Arc, Mutex},
+};
+
+use super::{
+    arithmetic::ArithmeticMode, io::StdIo, omega::Ω, stack::Stack, Machine, MEMORY_SIZE
+};
+use crate::{utils::array_debug::ArrayDebug, ConstantSizeString};
+
+/// The owned buffers of a [`Machine`].
+///
+/// Returned by [`Machine::into_parts`] and taken by [`Machine::from_parts`].
+#[derive(Clone)]
+pub struct MachineParts {
+    /// The memory
+    pub memory: Box<[u8; MEMORY_SIZE]>,
+    /// The data of the stack, whose capacity is the size of the stack
+    pub stack: Vec<u8>,
+}
+
+impl Debug for MachineParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MachineParts")
+            .field("memory", &(&self.memory).array_debug(16, 0))
+            .field("stack", &self.stack)
+            .finish()
+    }
+}
+
+impl Machine {
+    /// Takes the machine apart into its memory and stack, without copying them.
+    ///
+    /// Everything else (the registers and the settings) is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::Machine;
+    /// let mut machine = Machine::default();
+    /// machine.memory[1000] = 1;
+    /// machine.stack.vec.push(2);
+    ///
+    /// let mut parts = machine.into_parts();
+    /// parts.memory[1001] = 3;
+    ///
+    /// let machine = Machine::from_parts(parts);
+    /// assert_eq!(machine.memory[1000..1002], [1, 3]);
+    /// assert_eq!(machine.stack.vec, [2]);
+    /// ```
+    #[must_use]
+    pub fn into_parts(self) -> MachineParts {
+        MachineParts {
+            memory: self.memory,
+            stack: self.stack.vec,
+        }
+    }
+
+    /// Makes a machine from a memory and a stack, without copying them.
+    ///
+    /// Everything else is like in [`Machine::default`].
+    ///
+    /// The memory always has exactly [`MEMORY_SIZE`] bytes, but the stack isn't checked:
+    /// its capacity is taken as the size of the stack (4095 bytes by default),
+    /// so a `Vec` with a different capacity changes when the stack overflows.
+    #[must_use]
+    pub fn from_parts(parts: MachineParts) -> Self {
+        Self {
+            reg_a: 0,
+            reg_b: 0,
+            reg_L: 0,
+            reg_f: 0.0,
+            reg_ch: '\0',
+            reg_ř: [0; 37],
+            reg_ß: ConstantSizeString::with_capacity(255),
+            reg_Ω: Ω::ZEROED,
+            num_reg: 0,
+            reg_ep: 0,
+            reg_dp: 0,
+            flag: false,
+            debug_mode: cfg!(debug_assertions),
+            halted: false,
+            halted_by: None,
+            arithmetic_mode: ArithmeticMode::Flag,
+            rng_state: 0,
+            memory: parts.memory,
+            stack: Stack { vec: parts.stack },
+            io: Arc::new(Mutex::new(StdIo)),
+            debug_to_stderr: false,
+            debug_memory_window: (16, 0),
+            suppress_io: false,
+            journal: None,
+            instructions_executed: 0,
+            opcode_counts: Box::new([0; 256]),
+            flag_stats: None,
+            trace: None,
+        }
+    }
+}
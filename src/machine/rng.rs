@@ -0,0 +1,40 @@
+//! The seedable pseudorandom number generator.
+//!
+//! More info at [`Machine::rng_state`].
+
+use super::Machine;
+
+impl Machine {
+    /// Returns the next pseudorandom number (`SplitMix64`) and advances [`rng_state`](Self::rng_state).
+    pub(super) const fn next_random(&mut self) -> u64 {
+        self.rng_state = self.rng_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudorandom number below `bound` (which has to be nonzero).
+    #[allow(clippy::cast_possible_truncation)]
+    pub(super) fn random_below(&mut self, bound: usize) -> usize {
+        (u128::from(self.next_random()).wrapping_mul(bound as u128) >> 64) as usize
+    }
+
+    /// Shuffles `len` bytes of memory starting at `start` (Fisher-Yates),
+    /// returning `false` without shuffling if they'd go past the end of memory.
+    pub(super) fn shuffle_memory(&mut self, start: u16, len: u16) -> bool {
+        let start = start as usize;
+        let end = start.saturating_add(len as usize);
+        if end > self.memory.len() {
+            return false;
+        }
+
+        for i in (start.saturating_add(1)..end).rev() {
+            let j =
+                start.saturating_add(self.random_below(i.saturating_sub(start).saturating_add(1)));
+            self.memory.swap(i, j);
+        }
+        true
+    }
+}
@@ -0,0 +1,250 @@
+//! Serialization of machine states (behind the `serde` feature).
+//!
+//! The IO backend, the journal, the flag statistics and the trace
+//! aren't part of the state, so they aren't serialized
+//! (a deserialized machine uses [`StdIo`](super::io::StdIo) and has them disabled).
+
+use std::{
+    fmt, str,
+    sync::{Arc, Mutex},
+};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser, Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{
+    decoder::{choice_from_byte, choice_to_byte},
+    io::{MachineIo, StdIo},
+    stack::Stack,
+};
+use crate::ConstantSizeString;
+
+/// The IO backend of a deserialized machine.
+pub(super) fn default_io() -> Arc<Mutex<dyn MachineIo + Send>> {
+    Arc::new(Mutex::new(StdIo))
+}
+
+/// The opcode counts of a deserialized machine.
+pub(super) fn zeroed_counts() -> Box<[u64; 256]> {
+    Box::new([0; 256])
+}
+
+/// Reads bytes that were written with [`Serializer::serialize_bytes`]
+/// (as bytes or as a sequence, depending on the format).
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Deserializes bytes written with [`Serializer::serialize_bytes`].
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+/// (De)serializes the memory as bytes.
+pub(super) mod memory {
+    use serde::{de, Deserializer, Serializer};
+
+    use crate::machine::MEMORY_SIZE;
+
+    /// Serializes the memory.
+    #[allow(clippy::borrowed_box)]
+    pub fn serialize<S: Serializer>(
+        memory: &Box<[u8; MEMORY_SIZE]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(memory.as_slice())
+    }
+
+    /// Deserializes the memory, checking that it's exactly [`MEMORY_SIZE`] bytes long.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Box<[u8; MEMORY_SIZE]>, D::Error> {
+        let bytes = super::deserialize_bytes(deserializer)?;
+        let len = bytes.len();
+
+        bytes
+            .into_boxed_slice()
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(len, &"65535 bytes"))
+    }
+}
+
+/// (De)serializes arrays that are too long for serde's own impls (like register ř).
+pub(super) mod array {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes an array as a sequence.
+    pub fn serialize<S: Serializer, T: Serialize, const N: usize>(
+        array: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        array.as_slice().serialize(serializer)
+    }
+
+    /// Deserializes an array from a sequence, checking its length.
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error> {
+        let vec = Vec::<T>::deserialize(deserializer)?;
+        let len = vec.len();
+
+        vec.try_into()
+            .map_err(|_| de::Error::invalid_length(len, &"an array"))
+    }
+}
+
+/// (De)serializes an illusion of choice as its byte representation,
+/// since most formats can't tell `None` and `Some(None)` apart.
+pub(super) mod choice {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// An illusion of choice.
+    #[allow(clippy::option_option)]
+    type Choice = Option<Option<Option<Option<()>>>>;
+
+    /// Serializes an illusion of choice.
+    #[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(choice: &Choice, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(super::choice_to_byte(*choice))
+    }
+
+    /// Deserializes an illusion of choice, checking that it's a valid one.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Choice, D::Error> {
+        let byte = u8::deserialize(deserializer)?;
+
+        super::choice_from_byte(byte).ok_or_else(|| {
+            de::Error::invalid_value(
+                de::Unexpected::Unsigned(byte.into()),
+                &"an illusion of choice",
+            )
+        })
+    }
+}
+
+/// The serialized form of a [`Stack`] or a [`ConstantSizeString`],
+/// which keeps the capacity.
+#[derive(Serialize, Deserialize)]
+struct Capped<T> {
+    /// The capacity
+    capacity: usize,
+    /// The contents
+    contents: T,
+}
+
+/// The largest capacity of a deserialized [`Stack`].
+const MAX_STACK_CAPACITY: usize = u16::MAX as usize;
+
+/// The largest capacity of a deserialized [`ConstantSizeString`].
+const MAX_STRING_CAPACITY: usize = u8::MAX as usize;
+
+impl<T> Capped<T> {
+    /// Returns the capacity, rejecting it (before anything is allocated) if it's above `max`.
+    fn checked_capacity<E: de::Error>(&self, max: usize, expected: &str) -> Result<usize, E> {
+        if self.capacity > max {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(u64::try_from(self.capacity).unwrap_or(u64::MAX)),
+                &expected,
+            ));
+        }
+        Ok(self.capacity)
+    }
+}
+
+impl Serialize for Stack {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// The stack's bytes, serialized as bytes.
+        struct Bytes<'a>(&'a [u8]);
+
+        impl Serialize for Bytes<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+
+        Capped {
+            capacity: self.total_space(),
+            contents: Bytes(&self.vec),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Stack {
+    /// Deserializes a stack, checking that its bytes fit in its capacity.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// The stack's bytes, deserialized from bytes.
+        struct Bytes(Vec<u8>);
+
+        impl<'de> Deserialize<'de> for Bytes {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_bytes(deserializer).map(Self)
+            }
+        }
+
+        let capped: Capped<Bytes> = Capped::deserialize(deserializer)?;
+        let capacity =
+            capped.checked_capacity(MAX_STACK_CAPACITY, "a capacity of at most 65535")?;
+        let Bytes(bytes) = capped.contents;
+
+        let mut stack = Self::with_capacity(capacity);
+        stack
+            .push_bytes(&bytes)
+            .map_err(|_| de::Error::invalid_length(bytes.len(), &"at most the stack's capacity"))?;
+        Ok(stack)
+    }
+}
+
+impl Serialize for ConstantSizeString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Capped {
+            capacity: self.capacity(),
+            contents: str::from_utf8(self.as_bytes()).map_err(ser::Error::custom)?,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConstantSizeString {
+    /// Deserializes a string, checking that it fits in its capacity.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let capped = Capped::<String>::deserialize(deserializer)?;
+        let capacity = capped.checked_capacity(MAX_STRING_CAPACITY, "a capacity of at most 255")?;
+        let contents = capped.contents;
+
+        if contents.len() > capacity {
+            return Err(de::Error::invalid_length(
+                contents.len(),
+                &"at most the string's capacity",
+            ));
+        }
+
+        let mut vec = Vec::with_capacity(capacity);
+        vec.extend_from_slice(contents.as_bytes());
+        // SAFETY: `vec` holds the bytes of a `String`, which are valid UTF-8
+        Ok(unsafe { Self::new(vec) })
+    }
+}
@@ -25,30 +25,47 @@ pub struct Stack {
 
 impl Default for Stack {
     fn default() -> Self {
-        Self {
-            vec: Vec::with_capacity(4095),
-        }
+        Self::with_capacity(4095)
     }
 }
 
 impl Stack {
+    /// Makes an empty stack that can hold `bytes` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::machine::stack::Stack;
+    /// let mut stack = Stack::with_capacity(2);
+    ///
+    /// assert_eq!(stack.total_space(), 2);
+    /// assert!(stack.push_bytes(&[1, 2]).is_ok());
+    /// assert!(stack.push_byte(3).is_err());
+    /// ```
+    #[must_use]
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(bytes),
+        }
+    }
+
     /// Returns the capacity of the stack (how big it is) in bytes.
     #[inline]
     #[must_use]
-    pub fn total_space(&self) -> usize {
+    pub const fn total_space(&self) -> usize {
         self.vec.capacity()
     }
     /// Returns how much space of the stack has been used in bytes.
     #[inline]
     #[must_use]
-    pub fn used_space(&self) -> usize {
+    pub const fn used_space(&self) -> usize {
         self.vec.len()
     }
     /// Returns how much space is left of the stack in bytes.
     #[inline]
     #[must_use]
     #[allow(clippy::arithmetic_side_effects)]
-    pub fn space_left(&self) -> usize {
+    pub const fn space_left(&self) -> usize {
         self.total_space() - self.used_space()
     }
 
@@ -153,6 +170,36 @@ impl Stack {
             Ok(())
         }
     }
+    /// Empties the [`Stack`], keeping its capacity.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+    /// Shortens the [`Stack`] to `new_len` bytes, popping the rest.
+    ///
+    /// Does nothing if `new_len` isn't less than the [`used_space`](Self::used_space).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::machine::stack::Stack;
+    /// let mut stack = Stack::default();
+    /// stack.push_bytes(&[1, 2, 3]).unwrap();
+    ///
+    /// stack.truncate(5);
+    /// assert_eq!(stack.vec, [1, 2, 3]);
+    ///
+    /// stack.truncate(1);
+    /// assert_eq!(stack.vec, [1]);
+    ///
+    /// stack.clear();
+    /// assert_eq!(stack.used_space(), 0);
+    /// assert_eq!(stack.total_space(), 4095);
+    /// ```
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        self.vec.truncate(new_len);
+    }
     /// Pops a 16-bit big endian unsigned integer from the stack.
     pub fn pop_u16(&mut self) -> Option<u16> {
         let mut array = [0, 0];
@@ -188,6 +235,80 @@ impl Stack {
 
         Some(u64::from_be_bytes(array))
     }
+
+    /// Pushes a 16-bit big endian signed integer onto the stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StackOverflow`] and doesn't push anything if the stack doesn't have enough space left.
+    pub fn push_i16(&mut self, value: i16) -> Result<(), StackOverflow> {
+        self.push_bytes(&value.to_be_bytes())
+    }
+    /// Pushes a 32-bit big endian signed integer onto the stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StackOverflow`] and doesn't push anything if the stack doesn't have enough space left.
+    pub fn push_i32(&mut self, value: i32) -> Result<(), StackOverflow> {
+        self.push_bytes(&value.to_be_bytes())
+    }
+    /// Pushes a big endian 64-bit float onto the stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StackOverflow`] and doesn't push anything if the stack doesn't have enough space left.
+    pub fn push_f64(&mut self, value: f64) -> Result<(), StackOverflow> {
+        self.push_bytes(&value.to_be_bytes())
+    }
+    /// Pops a 16-bit big endian signed integer from the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::machine::stack::Stack;
+    /// let mut stack = Stack::default();
+    /// stack.push_i16(i16::MIN).unwrap();
+    ///
+    /// assert_eq!(stack.vec, [0x80, 0x00]);
+    /// assert_eq!(stack.pop_i16(), Some(i16::MIN));
+    /// assert_eq!(stack.pop_i16(), None);
+    /// ```
+    pub fn pop_i16(&mut self) -> Option<i16> {
+        self.pop_u16().map(|v| i16::from_be_bytes(v.to_be_bytes()))
+    }
+    /// Pops a 32-bit big endian signed integer from the stack.
+    pub fn pop_i32(&mut self) -> Option<i32> {
+        self.pop_u32().map(|v| i32::from_be_bytes(v.to_be_bytes()))
+    }
+    /// Pops a big endian 64-bit float from the stack.
+    pub fn pop_f64(&mut self) -> Option<f64> {
+        self.pop_u64().map(f64::from_bits)
+    }
+
+    /// Returns the last `N` bytes of the [`Stack`] without popping them.
+    fn peek_array<const N: usize>(&self) -> Option<[u8; N]> {
+        let start = self.used_space().checked_sub(N)?;
+        self.vec.get(start..)?.try_into().ok()
+    }
+    /// Returns the byte on top of the [`Stack`] without popping it.
+    ///
+    /// Returns [`None`] if there are no bytes on the [`Stack`].
+    #[must_use]
+    pub fn peek_byte(&self) -> Option<u8> {
+        self.vec.last().copied()
+    }
+    /// Returns the 16-bit big endian unsigned integer on top of the stack
+    /// without popping it (the value [`pop_u16`](Self::pop_u16) would return).
+    #[must_use]
+    pub fn peek_u16(&self) -> Option<u16> {
+        self.peek_array().map(u16::from_be_bytes)
+    }
+    /// Returns the 32-bit big endian unsigned integer on top of the stack
+    /// without popping it (the value [`pop_u32`](Self::pop_u32) would return).
+    #[must_use]
+    pub fn peek_u32(&self) -> Option<u32> {
+        self.peek_array().map(u32::from_be_bytes)
+    }
 }
 
 impl fmt::Debug for Stack {
@@ -0,0 +1,212 @@
+//! Execution traces for post-mortem analysis.
+//!
+//! More info at [`Machine::trace_to`].
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use super::{Machine, INVALID_OPCODE_MESSAGE};
+use crate::instruction::{Instruction, InstructionKind};
+
+/// The registers that are traced.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Traced {
+    /// register a
+    reg_a: u8,
+    /// register b
+    reg_b: i16,
+    /// register L
+    reg_L: u16,
+    /// register f
+    reg_f: f64,
+    /// register ch
+    reg_ch: char,
+    /// number register
+    num_reg: i32,
+    /// execution pointer
+    reg_ep: u16,
+    /// dot pointer
+    reg_dp: u16,
+    /// overflow/error flag
+    flag: bool,
+    /// length of the stack
+    stack_len: usize,
+}
+
+impl Traced {
+    /// Takes the traced registers of a machine.
+    pub(crate) const fn of(machine: &Machine) -> Self {
+        Self {
+            reg_a: machine.reg_a,
+            reg_b: machine.reg_b,
+            reg_L: machine.reg_L,
+            reg_f: machine.reg_f,
+            reg_ch: machine.reg_ch,
+            num_reg: machine.num_reg,
+            reg_ep: machine.reg_ep,
+            reg_dp: machine.reg_dp,
+            flag: machine.flag,
+            stack_len: machine.stack.vec.len(),
+        }
+    }
+}
+
+impl Machine {
+    /// Writes an execution trace to `w`, one line per executed instruction.
+    ///
+    /// Every line has the format
+    ///
+    /// ```text
+    /// <address> <opcode> [<register>=<value> ...]
+    /// ```
+    ///
+    /// where the address (4 digits) and the opcode (2 digits) are hexadecimal
+    /// and the registers are the ones the instruction changed, in this order:
+    ///
+    /// | register | value |
+    /// |----------|-------|
+    /// | `a`      | register A (decimal) |
+    /// | `b`      | register B (decimal) |
+    /// | `L`      | register L (decimal) |
+    /// | `f`      | register F (decimal, `NaN`, `inf` or `-inf`) |
+    /// | `ch`     | register Ch (decimal code point) |
+    /// | `num`    | number register (decimal) |
+    /// | `ep`     | execution pointer (decimal, only written for jumps) |
+    /// | `dp`     | dot pointer (decimal) |
+    /// | `flag`   | flag (`0` or `1`) |
+    /// | `stack`  | length of the stack (decimal) |
+    ///
+    /// Errors writing the trace are ignored.
+    /// Tracing is stopped by setting [`trace`](Self::trace) to `None`
+    /// and costs nothing when it's off, which is the default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, Machine};
+    /// # use std::{io::{self, Write}, sync::{Arc, Mutex}};
+    /// # #[derive(Clone)]
+    /// # struct Shared(Arc<Mutex<Vec<u8>>>);
+    /// # impl Write for Shared {
+    /// #     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.lock().unwrap().write(buf) }
+    /// #     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    /// # }
+    /// let trace = Shared(Arc::new(Mutex::new(Vec::new())));
+    ///
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 5;
+    ///         2: popa;
+    ///         3: Ωtheendisnear;
+    ///         4: Ωskiptothechase;
+    ///     },
+    ///     0,
+    /// );
+    /// machine.trace_to(Box::new(trace.clone()));
+    /// machine.run();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(trace.0.lock().unwrap().clone()).unwrap(),
+    ///     "0000 32 stack=1\n0002 34 a=5 stack=0\n0003 1a\n0004 1b\n",
+    /// );
+    /// ```
+    pub fn trace_to(&mut self, w: Box<dyn Write + Send>) {
+        self.trace = Some(Arc::new(Mutex::new(w)));
+    }
+
+    /// Runs the machine until it halts like [`run`](Self::run),
+    /// calling `f` after every executed instruction with its address,
+    /// the instruction and the machine.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an invalid opcode (instruction) is stumbled upon, like [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::{esoteric_assembly, instruction::Instruction, Machine};
+    /// let mut machine = Machine::default();
+    /// machine.load(
+    ///     &esoteric_assembly! {
+    ///         0: pushi 5;
+    ///         2: popa;
+    ///         3: halt;
+    ///     },
+    ///     0,
+    /// );
+    ///
+    /// let mut trace = Vec::new();
+    /// let exit_code = machine.run_traced(|address, instruction, machine| {
+    ///     trace.push((address, *instruction, machine.reg_a));
+    /// });
+    ///
+    /// assert_eq!(exit_code, 5);
+    /// assert_eq!(
+    ///     trace,
+    ///     [
+    ///         (0, Instruction::Pushi(5), 0),
+    ///         (2, Instruction::Popa, 5),
+    ///         (3, Instruction::Halt, 5),
+    ///     ]
+    /// );
+    /// ```
+    pub fn run_traced<F: FnMut(u16, &Instruction, &Self)>(&mut self, mut f: F) -> u8 {
+        while !self.halted {
+            let address = self.reg_ep;
+
+            #[allow(clippy::expect_used)]
+            if let Some(instruction) = self.step_instruction().expect(INVALID_OPCODE_MESSAGE) {
+                f(address, &instruction, self);
+            }
+        }
+        self.reg_a
+    }
+
+    /// Writes the trace line of an executed instruction.
+    pub(crate) fn write_trace(&self, before: Traced, instruction: Instruction) {
+        use std::fmt::Write as _;
+
+        let Some(trace) = &self.trace else {
+            return;
+        };
+
+        let kind = InstructionKind::from(instruction);
+        let after = Traced::of(self);
+        let mut line = format!("{:04x} {:02x}", before.reg_ep, kind as u8);
+
+        macro_rules! changed {
+            ($name:literal, $field:ident $(, $map:path)?) => {
+                if before.$field != after.$field {
+                    let _ = write!(line, concat!(" ", $name, "={}"), $($map)?(after.$field));
+                }
+            };
+        }
+
+        changed!("a", reg_a);
+        changed!("b", reg_b);
+        changed!("L", reg_L);
+        if before.reg_f.to_bits() != after.reg_f.to_bits() {
+            let _ = write!(line, " f={}", after.reg_f);
+        }
+        changed!("ch", reg_ch, u32::from);
+        changed!("num", num_reg);
+        if after.reg_ep != before.reg_ep.wrapping_add(kind.encoded_len()) {
+            let _ = write!(line, " ep={}", after.reg_ep);
+        }
+        changed!("dp", reg_dp);
+        if before.flag != after.flag {
+            let _ = write!(line, " flag={}", u8::from(after.flag));
+        }
+        changed!("stack", stack_len);
+        line.push('\n');
+
+        if let Ok(mut trace) = trace.lock() {
+            let _ = trace.write_all(line.as_bytes());
+        }
+    }
+}
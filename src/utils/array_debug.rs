@@ -50,7 +50,7 @@ impl<'a, T: Debug> DebugArray<'a, T> {
     }
 }
 
-impl<'a, T: Debug> Debug for DebugArray<'a, T> {
+impl<T: Debug> Debug for DebugArray<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut ret = f.debug_list();
         let mut ret = ret.entries(self.0);
@@ -75,7 +75,7 @@ impl<'a, T: Debug> Debug for DebugArray<'a, T> {
 #[repr(transparent)]
 pub struct DebugArrayDebugger<'a, T: Debug>(DebugArray<'a, T>);
 
-impl<'a, T: Debug> Debug for DebugArrayDebugger<'a, T> {
+impl<T: Debug> Debug for DebugArrayDebugger<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("{:?}", self.0))
     }
@@ -23,14 +23,53 @@ impl ConstantSizeString {
     /// The caller must guarantee that `vec` is valid UTF-8.
     #[inline]
     #[must_use]
-    pub unsafe fn new(vec: Vec<u8>) -> Self {
+    pub const unsafe fn new(vec: Vec<u8>) -> Self {
         Self { vec }
     }
+    /// Make a new [`ConstantSizeString`], checking that `vec` is valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Utf8Error`](str::Utf8Error) if `vec` isn't valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::ConstantSizeString;
+    /// assert!(ConstantSizeString::try_new(b"hello".to_vec()).is_ok());
+    /// assert!(ConstantSizeString::try_new(vec![0xFF]).is_err());
+    /// ```
+    pub fn try_new(vec: Vec<u8>) -> Result<Self, str::Utf8Error> {
+        str::from_utf8(&vec)?;
+        Ok(Self { vec })
+    }
+    /// Make a new empty [`ConstantSizeString`] that can hold `capacity` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::ConstantSizeString;
+    /// let string = ConstantSizeString::with_capacity(16);
+    ///
+    /// assert!(string.is_empty());
+    /// assert_eq!(string.capacity(), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
     /// Pushes a byte onto the [`ConstantSizeString`].
     ///
     /// If there is available space, it pushes the byte,
     /// Returns [`Overflow`] if there is no more available space.
     ///
+    /// # Errors
+    ///
+    /// Returns [`Overflow`] if there is no more available space.
+    ///
     /// # Safety
     ///
     /// The caller must guarantee that `byte` is valid UTF-8.
@@ -60,6 +99,10 @@ impl ConstantSizeString {
     /// If there is enough available space, it pushes the byte,
     /// Returns [`Overflow`] if there is not enough available space.
     ///
+    /// # Errors
+    ///
+    /// Returns [`Overflow`] if there is not enough available space.
+    ///
     /// # Safety
     ///
     /// The caller must guarantee that `bytes` are valid UTF-8.
@@ -103,13 +146,57 @@ impl ConstantSizeString {
     /// Gets the length of the string.
     #[inline]
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.vec.len()
     }
 
+    /// Gets the capacity of the string (how many bytes fit in it).
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Gets the bytes of the string.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Returns an iterator over the bytes of the string.
+    #[inline]
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.vec.iter().copied()
+    }
+
+    /// Returns an iterator over the characters of the string.
+    ///
+    /// Invalid UTF-8 is replaced with replacement characters (`�`),
+    /// like when the string is printed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use esoteric_vm::ConstantSizeString;
+    /// let string = ConstantSizeString::try_new("héllo".as_bytes().to_vec()).unwrap();
+    ///
+    /// assert_eq!(string.chars().collect::<String>(), "héllo");
+    /// assert_eq!(string.chars().count(), 5);
+    /// assert_eq!(string.bytes().count(), 6);
+    /// ```
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.vec.utf8_chunks().flat_map(|chunk| {
+            chunk
+                .valid()
+                .chars()
+                .chain((!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER))
+        })
+    }
+
     /// Checks if the string is empty.
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
@@ -119,6 +206,10 @@ impl ConstantSizeString {
         self.vec.get(index).copied()
     }
     /// Sets a byte in the string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Overflow`] if `index` is out of bounds.
     pub fn set(&mut self, index: usize, value: u8) -> Result<(), Overflow> {
         self.vec.get_mut(index).map_or(Err(Overflow), |v| {
             *v = value;
@@ -128,19 +219,16 @@ impl ConstantSizeString {
 }
 
 impl fmt::Debug for ConstantSizeString {
+    /// Invalid UTF-8 is shown with replacement characters (`�`).
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        #[allow(clippy::expect_used)]
-        fmt::Debug::fmt(
-            str::from_utf8(&self.vec).expect("invalid `ConstantSizeString` print attempts"),
-            f,
-        )
+        fmt::Debug::fmt(&String::from_utf8_lossy(&self.vec), f)
     }
 }
 
 impl fmt::Display for ConstantSizeString {
+    /// Invalid UTF-8 is shown with replacement characters (`�`).
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        #[allow(clippy::expect_used)]
-        f.write_str(str::from_utf8(&self.vec).expect("invalid `ConstantSizeString` print attempts"))
+        f.write_str(&String::from_utf8_lossy(&self.vec))
     }
 }
 
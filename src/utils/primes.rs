@@ -1,7 +1,7 @@
 //! A list of primes and semiprimes that are also fibonacci numbers.
 //!
 //! Determined with :
-//! ```rust,ingore
+//! ```rust,ignore
 //!     use std::collections::HashSet;
 //!     use std::io::Write;
 //!     // PRIMES
@@ -106,6 +106,10 @@
 //!         len = fibonacci_semiprimes_list.len()
 //!     )
 //! ```
+//!
+//! The [`u32`] list was determined the same way, with the fibonacci numbers
+//! below [`u32::MAX`] and checking every one of them by trial division
+//! with the primes below 65536 (which is enough for any [`u32`]).
 
 /// A sorted list of [`u16`] primes and semprimes that are also fibonacci numbers.
 ///
@@ -116,9 +120,78 @@ pub const _FIB_PRIME_AND_SEMIPRIME_LIST_U16: [u16; 15] = [
 /// Checks if a [`u16`] is a prime or semiprime and a fibonacci number.
 ///
 /// The list of numbers is as follows: 1, 2, 3, 5, 13, 21, 34, 55, 89, 233, 377, 1597, 4181, 17711, 28657
+#[must_use]
 pub const fn is_fib_prime_or_semiprime_u16(n: u16) -> bool {
     matches!(
         n,
         1 | 2 | 3 | 5 | 13 | 21 | 34 | 55 | 89 | 233 | 377 | 1597 | 4181 | 17711 | 28657
     )
 }
+
+/// A sorted list of [`u32`] primes and semiprimes that are also fibonacci numbers.
+///
+/// It starts with [`_FIB_PRIME_AND_SEMIPRIME_LIST_U16`] and continues with
+/// 121393, 514229, 1346269, 5702887, 165580141, 433494437, 2971215073
+pub const FIB_PRIME_AND_SEMIPRIME_LIST_U32: &[u32] = &[
+    1,
+    2,
+    3,
+    5,
+    13,
+    21,
+    34,
+    55,
+    89,
+    233,
+    377,
+    1597,
+    4181,
+    17711,
+    28657,
+    121_393,
+    514_229,
+    1_346_269,
+    5_702_887,
+    165_580_141,
+    433_494_437,
+    2_971_215_073,
+];
+/// Checks if a [`u32`] is a prime or semiprime and a fibonacci number.
+///
+/// The list of numbers is [`FIB_PRIME_AND_SEMIPRIME_LIST_U32`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use esoteric_vm::primes::is_fib_prime_or_semiprime_u32;
+/// assert!(is_fib_prime_or_semiprime_u32(28657));
+/// assert!(is_fib_prime_or_semiprime_u32(2_971_215_073));
+/// assert!(!is_fib_prime_or_semiprime_u32(46368));
+/// ```
+#[must_use]
+pub const fn is_fib_prime_or_semiprime_u32(n: u32) -> bool {
+    matches!(
+        n,
+        1 | 2
+            | 3
+            | 5
+            | 13
+            | 21
+            | 34
+            | 55
+            | 89
+            | 233
+            | 377
+            | 1597
+            | 4181
+            | 17711
+            | 28657
+            | 121_393
+            | 514_229
+            | 1_346_269
+            | 5_702_887
+            | 165_580_141
+            | 433_494_437
+            | 2_971_215_073
+    )
+}
@@ -0,0 +1,90 @@
+//! Checks `call` and `ret` with a recursive factorial.
+
+use esoteric_vm::{esoteric_assembly, Machine};
+
+/// Computes `n!` into register L with nested calls.
+fn factorial(n: u8) -> Machine {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            // b = n
+            0: pushi 0;
+            2: pushi n;
+            4: popb;
+            5: call 10;
+            8: Ωtheendisnear;
+            9: Ωskiptothechase;
+
+            // L = b!
+            10: pushi 0;
+            12: pushi 32;
+            14: zpopep;
+            15: stackdealloc 2;
+            // L = (b - 1)!
+            18: pushb;
+            19: pushb;
+            20: popl;
+            21: subil 1;
+            24: pushl;
+            25: popb;
+            26: call 10;
+            // L *= b
+            29: popb;
+            30: mulbl;
+            31: ret;
+
+            // L = 1
+            32: pushi 0;
+            34: pushi 1;
+            36: popl;
+            37: ret;
+        },
+        0,
+    );
+    machine.run();
+    machine
+}
+
+#[test]
+fn recursive_factorial() {
+    for (n, expected) in [(0, 1), (1, 1), (5, 120), (8, 40320)] {
+        let machine = factorial(n);
+
+        assert_eq!(machine.reg_L, expected);
+        assert!(!machine.flag);
+        assert_eq!(machine.stack.used_space(), 0);
+    }
+}
+
+#[test]
+fn ret_sets_the_flag_on_an_empty_stack() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: ret;
+            1: Ωtheendisnear;
+            2: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert!(machine.flag);
+}
+
+#[test]
+fn call_sets_the_flag_on_a_full_stack() {
+    let mut machine = Machine::default().with_stack_capacity(1);
+    machine.load(
+        &esoteric_assembly! {
+            0: call 100;
+            3: Ωtheendisnear;
+            4: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert!(machine.flag);
+    assert_eq!(machine.reg_ep, 5);
+}
@@ -0,0 +1,53 @@
+//! Checks constant size strings.
+
+use esoteric_vm::{ConstantSizeString, Overflow};
+
+#[test]
+fn invalid_utf8_is_printed_lossily() {
+    let mut string = ConstantSizeString::try_new(Vec::with_capacity(8)).unwrap();
+    // SAFETY: printing is the only thing done with the string, which accepts invalid UTF-8
+    unsafe { string.push_bytes(b"a\xFFb").unwrap() };
+
+    assert_eq!(string.to_string(), "a\u{FFFD}b");
+    assert_eq!(format!("{string:?}"), "\"a\u{FFFD}b\"");
+}
+
+#[test]
+fn valid_utf8_is_printed_as_is() {
+    let string = ConstantSizeString::try_new("ßΩ\n".as_bytes().to_vec()).unwrap();
+
+    assert_eq!(string.to_string(), "ßΩ\n");
+    assert_eq!(format!("{string:?}"), "\"ßΩ\\n\"");
+}
+
+#[test]
+fn pushing_past_the_capacity_overflows() {
+    let mut string = ConstantSizeString::with_capacity(16);
+    assert_eq!(string.capacity(), 16);
+
+    for _ in 0..16 {
+        // SAFETY: `b'a'` is valid UTF-8
+        assert_eq!(unsafe { string.push_byte(b'a') }, Ok(()));
+    }
+    // SAFETY: `b'a'` is valid UTF-8
+    assert_eq!(unsafe { string.push_byte(b'a') }, Err(Overflow));
+    assert_eq!(string.len(), 16);
+    assert_eq!(string.capacity(), 16);
+}
+
+#[test]
+fn iterating_over_multi_byte_characters() {
+    let string = ConstantSizeString::try_new("héllo".as_bytes().to_vec()).unwrap();
+
+    assert_eq!(string.bytes().collect::<Vec<_>>(), "héllo".as_bytes());
+    assert_eq!(
+        string.chars().collect::<Vec<_>>(),
+        ['h', 'é', 'l', 'l', 'o']
+    );
+    assert_ne!(string.chars().count(), string.bytes().count());
+
+    let mut string = ConstantSizeString::with_capacity(8);
+    // SAFETY: only the characters are read, which accepts invalid UTF-8
+    unsafe { string.push_bytes(b"a\xFF\xFEb").unwrap() };
+    assert_eq!(string.chars().collect::<String>(), string.to_string());
+}
@@ -0,0 +1,68 @@
+//! Checks the instruction counters.
+
+use std::sync::{Arc, Mutex};
+
+use esoteric_vm::{
+    esoteric_assembly,
+    instruction::{Instruction, InstructionKind},
+    machine::journal::Journal,
+    Machine,
+};
+
+/// The machine of the `helloworld` example, writing to `output`.
+fn hello_world(output: Arc<Mutex<Vec<u8>>>) -> Machine {
+    let mut machine = Machine::default().with_output(output);
+    machine.load(
+        &esoteric_assembly! {
+            0: pushi b'.';
+            2: pop 28657;
+            5: ldidp 28657;
+            8: writeline 13;
+            11: Ωtheendisnear;
+            12: Ωskiptothechase;
+            13: data b"Hello, world!\n\0";
+        },
+        0,
+    );
+    machine
+}
+
+#[test]
+fn hello_world_executes_six_instructions() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = hello_world(Arc::clone(&output));
+    machine.run();
+
+    assert_eq!(output.lock().unwrap().as_slice(), b"Hello, world!\n");
+    assert_eq!(machine.instructions_executed, 6);
+    assert_eq!(machine.opcode_counts.iter().sum::<u64>(), 6);
+    assert_eq!(machine.opcode_count(InstructionKind::WriteLine), 1);
+    assert_eq!(machine.opcode_count(InstructionKind::Nop), 0);
+
+    let clone = machine.clone();
+    assert_eq!(clone.instructions_executed, 6);
+    assert_eq!(clone.opcode_counts, machine.opcode_counts);
+
+    machine.reset_counters();
+    assert_eq!(machine.instructions_executed, 0);
+    assert_eq!(machine.opcode_counts.iter().sum::<u64>(), 0);
+}
+
+#[test]
+fn the_journal_counts_every_instruction_once() {
+    let mut machine = hello_world(Arc::new(Mutex::new(Vec::new())));
+    machine.journal = Some(Journal::default());
+    machine.run();
+
+    assert_eq!(machine.instructions_executed, 6);
+    assert_eq!(machine.opcode_counts.iter().sum::<u64>(), 6);
+}
+
+#[test]
+fn executing_directly_isnt_a_fetch() {
+    let mut machine = Machine::default();
+    machine.execute_instruction(Instruction::Nop);
+
+    assert_eq!(machine.instructions_executed, 1);
+    assert_eq!(machine.opcode_count(InstructionKind::Nop), 0);
+}
@@ -0,0 +1,397 @@
+//! Checks that the instruction tables (the [`Instruction`] enum, decoding,
+//! executing, loading and the `esoteric_assembly!` macro) agree with each other.
+
+use esoteric_vm::{
+    assembly::{assemble, mnemonics},
+    esoteric_assembly,
+    instruction::{DataOrInstruction, Instruction, InstructionKind},
+    Machine,
+};
+
+/// The pseudo-instructions listed by [`mnemonics`], which aren't instructions.
+const DIRECTIVES: [&str; 5] = ["data", "byte", "fill", "align", "pushstr"];
+
+/// Assembles the program with the assembly macro
+/// and also returns its source for the runtime assembler.
+macro_rules! assembly_and_source {
+    ($($t:tt)*) => {
+        (esoteric_assembly! { $($t)* }, stringify!($($t)*))
+    };
+}
+
+/// One of every instruction with arbitrary operands,
+/// written with the assembly macro so that every macro rule gets compiled,
+/// and the source of it.
+fn every_instruction_and_source() -> (Vec<Instruction>, &'static str) {
+    let (asm, source) = assembly_and_source! {
+        nop;
+
+        ldar 1000;
+        ldarl;
+        starl;
+        memfilla 1000, 16;
+        memcpy 1, 2, 3;
+        memset 1, 2, 3;
+        shuffle 1000, 16;
+        sba;
+        swapab;
+        movab;
+        movba;
+        rotla 3;
+        rotra 3;
+        atodec 1000;
+
+        clř;
+        dumpř 1000;
+        movař 1;
+        setř 1, 1000;
+        setiř 1, -1;
+        ldř 1000;
+        ldiř [-2; 37];
+
+        clß;
+        dumpß 1000;
+        writeß 1000, 1;
+        movaß 1;
+        setß 1000, 1;
+        setiß 1, 1;
+        ldß 1000;
+        pushß;
+        popß;
+        lenßa;
+        lenßl;
+        jßeq 1000, 0;
+
+        ldidp 28657;
+        checkdp;
+        ldinum -123_456;
+        ldif -2.5;
+
+        Ωchoiceset Some(Some(None));
+        Ωchoicegeta;
+
+        Ωgainapolymorphicdesires;
+        Ωloseapolymorphicdesires;
+        Ωpushpolymorphicdesires;
+
+        Ωtheendisnear;
+        Ωskiptothechase;
+
+        Ωsetsentience true;
+        Ωsetpaperclipproduction true;
+
+        addbl;
+        subbl;
+        addil -3;
+        subil 3;
+        incl;
+        decl;
+        mulbl;
+        divbl;
+        modbl;
+
+        notl;
+        andbl;
+        orbl;
+        xorbl;
+        popcntl;
+        ctzl;
+        clzl;
+
+        cmplb;
+        clampl;
+        cmpeqlb;
+        cmpltlb;
+        cmpgtlb;
+
+        tgflag;
+        clflag;
+        pushflagclear;
+        popflag;
+
+        addf 1000;
+        subf 1000;
+        mulf 1000;
+        divf 1000;
+        modf 1000;
+        sqrtf;
+        floorf;
+        ceilf;
+        roundf;
+        ftol;
+        ltof;
+
+        stackalloc 10;
+        stackdealloc 10;
+        dup;
+        swap;
+        stackclear;
+
+        push 1000;
+        pushi 1;
+        pushi16 300;
+        pop 1000;
+        popmem 1000, 2;
+
+        popa;
+        pusha;
+        popb;
+        pushb;
+        popl;
+        pushl;
+        sptol;
+        eptol;
+        popf;
+        pushf;
+        popch;
+        pushch;
+        popnum;
+        pushnum;
+
+        popep;
+        zpopep;
+        ppopep;
+        npopep;
+        fpopep;
+        zapopep;
+        dpopep;
+        jmpl;
+        jmp -3;
+        jz -3;
+        jnz -3;
+        jf -3;
+        fjmpclear 300;
+        call 1000;
+        ret;
+        halt;
+        beqai 1, 1000;
+        jmemeq 1000, b'.', 300;
+
+        getchar;
+        getline;
+        readnum;
+        getbyte;
+        ateof;
+
+        writechar;
+        putbyte;
+        writenum;
+        writelineß;
+        writeline 1000;
+        writelineifflag 1000;
+        writestack 0, 0;
+
+        toggledebug;
+        debugmachinestate;
+        debugmachinestatecompact;
+        debugmemoryregion 1000, 1010;
+        debugstackregion 0, 0;
+        showchoice;
+    };
+
+    let instructions = asm
+        .into_iter()
+        .map(|v| match v {
+            DataOrInstruction::Instruction(instruction) => instruction,
+            DataOrInstruction::ByteData(_)
+            | DataOrInstruction::Data(_)
+            | DataOrInstruction::Zeroed(_)
+            | DataOrInstruction::Fill(_, _)
+            | DataOrInstruction::Align(_)
+            | DataOrInstruction::PushString(_) => unreachable!(),
+        })
+        .collect();
+
+    (instructions, source)
+}
+
+/// One of every instruction with arbitrary operands.
+fn every_instruction() -> Vec<Instruction> {
+    every_instruction_and_source().0
+}
+
+#[test]
+fn every_kind_is_covered() {
+    let kinds: Vec<InstructionKind> = every_instruction()
+        .iter()
+        .map(InstructionKind::from)
+        .collect();
+
+    for opcode in 0..=u8::MAX {
+        if let Some(kind) = InstructionKind::from_repr(opcode) {
+            assert!(
+                kinds.contains(&kind),
+                "{kind:?} is missing from `every_instruction`"
+            );
+        }
+    }
+
+    assert_eq!(
+        kinds.len(),
+        (0..=u8::MAX).filter_map(InstructionKind::from_repr).count(),
+        "`every_instruction` has duplicates"
+    );
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    for instruction in every_instruction() {
+        let mut machine = Machine::default();
+        let len = machine.load(&[DataOrInstruction::Instruction(instruction)], 0);
+
+        assert_eq!(machine.memory[0], InstructionKind::from(instruction) as u8);
+        assert_eq!(
+            machine.scan_instructions(0, len),
+            Ok(vec![(0, instruction)]),
+            "{instruction:?} doesn't decode to itself"
+        );
+
+        let decoded = machine.fetch_instruction();
+        assert_eq!(decoded, Some(instruction));
+        assert_eq!(
+            machine.reg_ep, len,
+            "{instruction:?} decodes to a different length"
+        );
+
+        let mut reloaded = Machine::default();
+        let reloaded_len = reloaded.load(&[DataOrInstruction::Instruction(instruction)], 0);
+        assert_eq!(reloaded_len, len);
+        assert_eq!(
+            reloaded.memory[..len as usize],
+            machine.memory[..len as usize],
+            "{instruction:?} loads to different bytes"
+        );
+
+        // IO instructions would wait for the terminal
+        if !instruction.is_io() {
+            machine.execute_instruction(instruction);
+        }
+    }
+}
+
+#[test]
+fn opcodes_dont_move() {
+    // the opcodes of the first instructions, which programs saved as bytes depend on
+    assert_eq!(InstructionKind::Nop as u8, 0);
+    assert_eq!(InstructionKind::Ldar as u8, 1);
+    assert_eq!(InstructionKind::AddBL as u8, 30);
+    assert_eq!(InstructionKind::Pushi as u8, 50);
+    assert_eq!(InstructionKind::Dpopep as u8, 70);
+    assert_eq!(InstructionKind::GetChar as u8, 71);
+    assert_eq!(InstructionKind::ShowChoice as u8, 81);
+
+    // instructions added later come after them, in the order they were added
+    assert_eq!(InstructionKind::JmpL as u8, 82);
+    assert_eq!(InstructionKind::LdarL as u8, 99);
+    assert_eq!(InstructionKind::PushiU16 as u8, 142);
+}
+
+#[test]
+fn encoded_len_adds_up_to_the_loaded_length() {
+    let instructions = every_instruction();
+    let total: u16 = instructions.iter().map(Instruction::encoded_len).sum();
+
+    let mut machine = Machine::default();
+    assert_eq!(machine.load_instructions(&instructions, 0), total);
+}
+
+#[test]
+fn display_is_assembly() {
+    assert_eq!(Instruction::Nop.to_string(), "nop");
+    assert_eq!(Instruction::Pushi(46).to_string(), "pushi 46");
+    assert_eq!(Instruction::WriteLine(13).to_string(), "writeline 13");
+    assert_eq!(Instruction::Setiř(2, -5).to_string(), "setiř 2, -5");
+    assert_eq!(Instruction::StackDup.to_string(), "dup");
+    assert_eq!(
+        Instruction::ΩSetSentience(true).to_string(),
+        "\u{2126}setsentience true"
+    );
+    assert_eq!(
+        Instruction::ΩChoiceSet(Some(None)).to_string(),
+        "\u{2126}choiceset Some(None)"
+    );
+    assert_eq!(
+        Instruction::Ldiř([1; 37]).to_string(),
+        format!("ldiř {:?}", [1; 37])
+    );
+}
+
+#[test]
+fn display_round_trips_through_the_assembler() {
+    let non_finite = [f64::INFINITY, f64::NEG_INFINITY, f64::NAN]
+        .map(|value| Instruction::Ldif(value.to_bits()));
+
+    for instruction in every_instruction().into_iter().chain(non_finite) {
+        let text = instruction.to_string();
+        let assembled = assemble(&text).unwrap_or_else(|err| panic!("`{text}`: {err}"));
+
+        assert!(
+            matches!(
+                assembled.as_slice(),
+                [DataOrInstruction::Instruction(v)] if *v == instruction
+            ),
+            "`{text}` doesn't assemble to {instruction:?}"
+        );
+    }
+}
+
+#[test]
+fn assembler_agrees_with_macro() {
+    let (instructions, source) = every_instruction_and_source();
+
+    let assembled: Vec<Instruction> = assemble(source)
+        .unwrap()
+        .into_iter()
+        .map(|v| match v {
+            DataOrInstruction::Instruction(instruction) => instruction,
+            DataOrInstruction::ByteData(_)
+            | DataOrInstruction::Data(_)
+            | DataOrInstruction::Zeroed(_)
+            | DataOrInstruction::Fill(_, _)
+            | DataOrInstruction::Align(_)
+            | DataOrInstruction::PushString(_) => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(assembled, instructions);
+}
+
+#[test]
+fn mnemonics_match_the_macro() {
+    // identifiers are NFC normalized, which turns the Ohm sign into a Greek Omega
+    let source = every_instruction_and_source()
+        .1
+        .replace('\u{3a9}', "\u{2126}");
+
+    let mut used: Vec<&str> = source
+        .split(';')
+        .filter_map(|statement| statement.split_whitespace().next())
+        // the `;` of array operands splits statements too
+        .filter(|name| name.starts_with(char::is_alphabetic))
+        .collect();
+    let mut listed: Vec<&str> = mnemonics()
+        .iter()
+        .copied()
+        .filter(|name| !DIRECTIVES.contains(name))
+        .collect();
+    used.sort_unstable();
+    listed.sort_unstable();
+
+    assert_eq!(used, listed);
+}
+
+#[test]
+fn pushstr_is_a_pushi_per_byte() {
+    let mut pushstr = Machine::default();
+    let end = pushstr.load(&esoteric_assembly! { pushstr b"hi" }, 0);
+
+    let mut pushi = Machine::default();
+    pushi.load(&esoteric_assembly! { pushi b'h'; pushi b'i' }, 0);
+
+    assert_eq!(end, 4);
+    assert_eq!(pushstr.memory, pushi.memory);
+    assert_eq!(
+        assemble("pushstr b\"hi\"").unwrap().len(),
+        esoteric_assembly! { pushi b'h'; pushi b'i' }.len()
+    );
+}
@@ -0,0 +1,821 @@
+//! Checks the behavior of single instructions.
+
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+
+use esoteric_vm::{
+    esoteric_assembly,
+    instruction::Instruction,
+    machine::{
+        arithmetic::ArithmeticMode,
+        halt::HaltReason,
+        io::{MachineIo, StreamIo},
+        journal::Journal,
+        RunOutcome,
+    },
+    Machine,
+};
+
+/// A machine with `io` as its IO backend and a valid dot pointer.
+fn machine_with_io(io: impl MachineIo + Send + 'static) -> Machine {
+    let mut machine = Machine::default().with_io(io);
+    machine.memory[28657] = b'.';
+    machine.execute_instruction(Instruction::Ldidp(28657));
+    machine
+}
+
+#[test]
+fn ldidp_sets_the_flag_on_invalid_addresses() {
+    let mut machine = Machine::default();
+    machine.execute_instruction(Instruction::Ldidp(28657));
+    assert_eq!(machine.reg_dp, 28657);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::Ldidp(100));
+    assert_eq!(machine.reg_dp, 28657);
+    assert!(machine.flag);
+}
+
+#[test]
+fn getline_reads_a_line_into_reg_ß() {
+    let mut machine = machine_with_io(
+        StreamIo::default().with_input(Arc::new(Mutex::new(Cursor::new(b"hello\nworld\n")))),
+    );
+
+    machine.execute_instruction(Instruction::GetLine);
+    assert_eq!(machine.reg_ß.as_bytes(), b"hello");
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::GetLine);
+    assert_eq!(machine.reg_ß.as_bytes(), b"world");
+    assert!(!machine.flag);
+}
+
+#[test]
+fn getline_sets_the_flag_on_long_lines() {
+    let mut machine = machine_with_io(
+        StreamIo::default().with_input(Arc::new(Mutex::new(Cursor::new([b'a'; 256])))),
+    );
+
+    machine.execute_instruction(Instruction::GetLine);
+    assert!(machine.reg_ß.is_empty());
+    assert!(machine.flag);
+}
+
+#[test]
+fn setř_loads_memory_into_reg_ř() {
+    let mut machine = Machine::default();
+    for (i, byte) in machine.memory[1000..1037].iter_mut().enumerate() {
+        *byte = 200_u8.wrapping_add(i as u8);
+    }
+
+    for i in 0..37 {
+        machine.execute_instruction(Instruction::Setř(i, 1000 + u16::from(i)));
+    }
+    machine.execute_instruction(Instruction::Dumpř(2000));
+
+    assert_eq!(machine.memory[1000..1037], machine.memory[2000..2037]);
+}
+
+#[test]
+fn dup_and_swap() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: pushi 1;
+            2: pushi 2;
+            4: swap;
+            5: dup;
+            6: Ωtheendisnear;
+            7: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert_eq!(machine.stack.vec, [2, 1, 1]);
+    assert!(!machine.flag);
+}
+
+#[test]
+fn dup_and_swap_set_the_flag_on_short_stacks() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: dup;
+            1: Ωtheendisnear;
+            2: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+    assert!(machine.flag);
+
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: pushi 1;
+            2: swap;
+            3: Ωtheendisnear;
+            4: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+    assert!(machine.flag);
+    assert_eq!(machine.stack.vec, [1]);
+}
+
+#[test]
+fn comparisons_set_the_flag_without_changing_registers() {
+    let mut machine = Machine::default();
+
+    for (l, b, eq, lt, gt) in [
+        (5, 5, true, false, false),
+        (4, 5, false, true, false),
+        (40000, 5, false, false, true),
+        (0, -1, false, false, true),
+    ] {
+        (machine.reg_L, machine.reg_b) = (l, b);
+
+        for (instruction, expected) in [
+            (Instruction::CmpEqLB, eq),
+            (Instruction::CmpLtLB, lt),
+            (Instruction::CmpGtLB, gt),
+        ] {
+            machine.flag = !expected;
+            machine.execute_instruction(instruction);
+            assert_eq!(machine.flag, expected, "{instruction} with L={l}, B={b}");
+            assert_eq!((machine.reg_L, machine.reg_b), (l, b));
+        }
+    }
+}
+
+#[test]
+fn jmp_loops_anywhere_in_memory() {
+    let asm = esoteric_assembly! {
+        addbl;
+        jmp -4;
+    };
+
+    for start in [0, 1000, 0xFFFF - 4] {
+        let mut machine = Machine::default();
+        machine.load(&asm, start);
+        machine.reg_ep = start;
+        machine.reg_b = 1;
+
+        for _ in 0..10 {
+            machine.step();
+        }
+        assert_eq!(machine.reg_L, 5);
+        assert_eq!(machine.reg_ep, start);
+    }
+}
+
+#[test]
+fn jnz_counts_down_to_zero() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            subil 1;
+            pushl;
+            popb;
+            jnz -8;
+            Ωtheendisnear;
+            Ωskiptothechase;
+        },
+        0,
+    );
+    machine.reg_L = 5;
+
+    assert_eq!(machine.run_for(100), RunOutcome::Halted(0));
+    assert_eq!(machine.reg_L, 0);
+    assert_eq!(machine.reg_b, 0);
+}
+
+#[test]
+fn conditional_jumps() {
+    let mut machine = Machine::default();
+
+    for (instruction, jumps) in [
+        (Instruction::Jz(-10), true),
+        (Instruction::Jnz(-10), false),
+        (Instruction::Jf(-10), false),
+    ] {
+        machine.reg_ep = 100;
+        machine.execute_instruction(instruction);
+        assert_eq!(
+            machine.reg_ep,
+            if jumps { 90 } else { 100 },
+            "{instruction}"
+        );
+    }
+
+    machine.flag = true;
+    machine.execute_instruction(Instruction::Jf(10));
+    assert_eq!(machine.reg_ep, 110);
+}
+
+#[test]
+fn movab_zero_extends_and_movba_truncates() {
+    let mut machine = Machine::default();
+
+    for (a, b) in [(0, 0), (127, 127), (128, 128), (255, 255)] {
+        machine.reg_a = a;
+        machine.reg_b = -1;
+        machine.execute_instruction(Instruction::MovAB);
+        assert_eq!(machine.reg_b, b);
+    }
+
+    for (b, a) in [
+        (0, 0),
+        (255, 255),
+        (256, 0),
+        (0x1234, 0x34),
+        (-1, 255),
+        (-256, 0),
+        (i16::MIN, 0),
+    ] {
+        machine.reg_b = b;
+        machine.execute_instruction(Instruction::MovBA);
+        assert_eq!(machine.reg_a, a, "{b}");
+        assert_eq!(machine.reg_b, b);
+    }
+}
+
+#[test]
+fn incl_counts_up_in_a_loop() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            incl;
+            cmpeqlb;
+            jf 3;
+            jmp -8;
+            Ωtheendisnear;
+            Ωskiptothechase;
+        },
+        0,
+    );
+    machine.reg_b = 10;
+
+    assert_eq!(machine.run_for(100), RunOutcome::Halted(0));
+    assert_eq!(machine.reg_L, 10);
+}
+
+#[test]
+fn incl_and_decl_wrap_around() {
+    let mut machine = Machine::default();
+
+    machine.execute_instruction(Instruction::DecL);
+    assert_eq!(machine.reg_L, u16::MAX);
+    assert!(machine.flag);
+
+    machine.execute_instruction(Instruction::DecL);
+    assert_eq!(machine.reg_L, u16::MAX - 1);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::IncL);
+    machine.execute_instruction(Instruction::IncL);
+    assert_eq!(machine.reg_L, 0);
+    assert!(machine.flag);
+
+    machine.arithmetic_mode = ArithmeticMode::Trap;
+    machine.execute_instruction(Instruction::DecL);
+    assert_eq!(machine.reg_L, 0);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Trap));
+}
+
+#[test]
+fn addil_and_subil_trap_on_overflow() {
+    let mut machine = Machine {
+        reg_L: u16::MAX,
+        arithmetic_mode: ArithmeticMode::Trap,
+        ..Machine::default()
+    };
+
+    machine.execute_instruction(Instruction::AddiL(-1));
+    assert_eq!(machine.reg_L, u16::MAX - 1);
+    assert!(!machine.halted);
+
+    machine.execute_instruction(Instruction::AddiL(2));
+    assert_eq!(machine.reg_L, u16::MAX - 1);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Trap));
+
+    let mut machine = Machine {
+        arithmetic_mode: ArithmeticMode::Trap,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::SubiL(1));
+    assert_eq!(machine.reg_L, 0);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Trap));
+}
+
+#[test]
+fn divbl_by_zero_sets_the_flag() {
+    let mut machine = Machine {
+        reg_L: 7,
+        reg_b: 2,
+        flag: true,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::DivBL);
+    assert_eq!(machine.reg_L, 3);
+    assert!(!machine.flag);
+
+    machine.reg_b = 0;
+    machine.execute_instruction(Instruction::DivBL);
+    assert_eq!(machine.reg_L, 3);
+    assert!(machine.flag);
+
+    machine.arithmetic_mode = ArithmeticMode::Trap;
+    machine.execute_instruction(Instruction::DivBL);
+    assert_eq!(machine.reg_L, 3);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Trap));
+}
+
+#[test]
+fn modbl_by_zero_sets_the_flag() {
+    let mut machine = Machine {
+        reg_L: 7,
+        reg_b: 4,
+        flag: true,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::ModBL);
+    assert_eq!(machine.reg_L, 3);
+    assert!(!machine.flag);
+
+    machine.reg_b = 0;
+    machine.execute_instruction(Instruction::ModBL);
+    assert_eq!(machine.reg_L, 3);
+    assert!(machine.flag);
+
+    machine.arithmetic_mode = ArithmeticMode::Trap;
+    machine.execute_instruction(Instruction::ModBL);
+    assert_eq!(machine.reg_L, 3);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Trap));
+}
+
+#[test]
+fn ftol_truncates_and_clamps() {
+    let mut machine = Machine {
+        reg_f: 12.9,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::FtoL);
+    assert_eq!(machine.reg_L, 12);
+    assert!(!machine.flag);
+
+    machine.reg_f = -0.5;
+    machine.execute_instruction(Instruction::FtoL);
+    assert_eq!(machine.reg_L, 0);
+    assert!(!machine.flag);
+
+    machine.reg_f = 70000.0;
+    machine.execute_instruction(Instruction::FtoL);
+    assert_eq!(machine.reg_L, u16::MAX);
+    assert!(machine.flag);
+
+    machine.flag = false;
+    machine.reg_f = -3.0;
+    machine.execute_instruction(Instruction::FtoL);
+    assert_eq!(machine.reg_L, 0);
+    assert!(machine.flag);
+}
+
+#[test]
+fn ftol_leaves_reg_l_on_nan_and_infinity() {
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut machine = Machine {
+            reg_L: 42,
+            reg_f: value,
+            ..Machine::default()
+        };
+        machine.execute_instruction(Instruction::FtoL);
+        assert_eq!(machine.reg_L, 42, "{value}");
+        assert!(machine.flag, "{value}");
+    }
+}
+
+#[test]
+fn ltof_converts_reg_l() {
+    let mut machine = Machine {
+        reg_L: 65535,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::LtoF);
+    assert!((machine.reg_f - 65535.0).abs() < f64::EPSILON);
+
+    machine.reg_f = 0.25;
+    machine.execute_instruction(Instruction::FtoL);
+    machine.execute_instruction(Instruction::LtoF);
+    assert!(machine.reg_f.abs() < f64::EPSILON);
+}
+
+#[test]
+fn sqrtf_of_a_constant_from_memory() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            push 100;
+            push 101;
+            push 102;
+            push 103;
+            push 104;
+            push 105;
+            push 106;
+            push 107;
+            popf;
+            sqrtf;
+            halt;
+        },
+        0,
+    );
+    machine.load(&esoteric_assembly! { data &2.0f64.to_be_bytes(); }, 100);
+
+    machine.run();
+    assert!((machine.reg_f - std::f64::consts::SQRT_2).abs() < f64::EPSILON);
+    assert!(!machine.flag);
+}
+
+#[test]
+fn sqrtf_of_a_negative_is_nan() {
+    let mut machine = Machine {
+        reg_f: -4.0,
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::SqrtF);
+    assert!(machine.reg_f.is_nan());
+    assert!(machine.flag);
+}
+
+#[test]
+fn floorf_ceilf_and_roundf() {
+    let cases = [
+        (Instruction::FloorF, 2.5, 2.0),
+        (Instruction::FloorF, -2.5, -3.0),
+        (Instruction::CeilF, 2.5, 3.0),
+        (Instruction::CeilF, -2.5, -2.0),
+        (Instruction::RoundF, 2.5, 3.0),
+        (Instruction::RoundF, -2.5, -3.0),
+        (Instruction::RoundF, 2.4, 2.0),
+    ];
+
+    for (instruction, value, expected) in cases {
+        let mut machine = Machine {
+            reg_f: value,
+            ..Machine::default()
+        };
+        machine.execute_instruction(instruction);
+        assert!(
+            (machine.reg_f - expected).abs() < f64::EPSILON,
+            "{instruction:?} {value}"
+        );
+    }
+}
+
+#[test]
+fn writenum_writes_num_reg_as_decimal() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = machine_with_io(StreamIo::default().with_output(output.clone()));
+
+    machine.num_reg = -42;
+    machine.execute_instruction(Instruction::WriteNum);
+    assert_eq!(*output.lock().unwrap(), b"-42");
+    assert!(!machine.flag);
+
+    machine.memory[28657] = 0;
+    machine.execute_instruction(Instruction::WriteNum);
+    assert_eq!(*output.lock().unwrap(), b"-42");
+    assert!(machine.flag);
+}
+
+#[test]
+fn writenum_writes_nothing_with_suppressed_io() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = machine_with_io(StreamIo::default().with_output(output.clone()));
+    machine.suppress_io = true;
+
+    machine.num_reg = 7;
+    machine.execute_instruction(Instruction::WriteNum);
+    assert!(output.lock().unwrap().is_empty());
+    assert!(!machine.flag);
+    assert!(Instruction::WriteNum.is_io());
+}
+
+#[test]
+fn suppressed_io_sets_the_flag_like_normal_io() {
+    let mut machine = Machine {
+        suppress_io: true,
+        ..Machine::default()
+    };
+
+    machine.execute_instruction(Instruction::WriteLineIfFlag(0));
+    assert!(!machine.flag);
+    machine.execute_instruction(Instruction::GetChar);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::DebugMachineState);
+    assert!(machine.flag);
+
+    machine.flag = false;
+    machine.reg_dp = 0xFFFF;
+    machine.execute_instruction(Instruction::WriteLine(0));
+    assert!(machine.flag);
+}
+
+#[test]
+fn readnum_reads_nothing_with_suppressed_io() {
+    let mut machine = machine_with_io(
+        StreamIo::default().with_input(Arc::new(Mutex::new(Cursor::new(b"123\n")))),
+    );
+    machine.suppress_io = true;
+
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, 0);
+    assert!(!machine.flag);
+    assert!(Instruction::ReadNum.is_io());
+
+    machine.suppress_io = false;
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, 123);
+}
+
+#[test]
+fn readnum_parses_a_line_into_num_reg() {
+    let mut machine = machine_with_io(
+        StreamIo::default().with_input(Arc::new(Mutex::new(Cursor::new(b"123\n -7 \nabc\n\n")))),
+    );
+
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, 123);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, -7);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, -7);
+    assert!(machine.flag);
+
+    machine.flag = false;
+    machine.execute_instruction(Instruction::ReadNum);
+    assert_eq!(machine.num_reg, -7);
+    assert!(machine.flag);
+}
+
+#[test]
+fn pushi16_pushes_a_full_u16() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            pushi16 300;
+            popl;
+            pushi16 0xABCD;
+            pushi 0;
+            halt;
+        },
+        0,
+    );
+
+    machine.run();
+    assert_eq!(machine.reg_L, 300);
+    assert_eq!(machine.stack.vec, [0xAB, 0xCD, 0]);
+    assert!(!machine.flag);
+    assert_eq!(Instruction::PushiU16(300).to_string(), "pushi16 300");
+}
+
+#[test]
+fn halt_stops_run_with_reg_a() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            pushi 42;
+            popa;
+            halt;
+            pushi 0;
+            popa;
+        },
+        0,
+    );
+
+    assert_eq!(machine.run(), 42);
+    assert_eq!(machine.halt_reason(), Some(HaltReason::Halt));
+    assert_eq!(machine.reg_ep, 4);
+}
+
+#[test]
+fn memcpy_handles_overlapping_ranges() {
+    let mut machine = Machine::default();
+
+    machine.load_bytes(b"abcdef", 100);
+    machine.execute_instruction(Instruction::MemCpy(102, 100, 4));
+    assert_eq!(machine.dump_memory(100, 106), b"ababcd");
+    assert!(!machine.flag);
+
+    machine.load_bytes(b"abcdef", 100);
+    machine.execute_instruction(Instruction::MemCpy(100, 102, 4));
+    assert_eq!(machine.dump_memory(100, 106), b"cdefef");
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::MemCpy(0xFFFF - 3, 100, 3));
+    assert_eq!(machine.dump_memory(0xFFFF - 3, 0xFFFF), b"cde");
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::MemCpy(0xFFFF - 3, 100, 4));
+    assert_eq!(machine.dump_memory(0xFFFF - 3, 0xFFFF), b"cde");
+    assert_eq!(machine.dump_memory(0, 1), b"f");
+    assert!(!machine.flag);
+}
+
+#[test]
+fn memcpy_wraps_around_the_end_of_memory() {
+    let mut machine = Machine::default();
+
+    machine.load_bytes(b"ab", 0xFFFF - 2);
+    machine.load_bytes(b"cd", 0);
+    machine.execute_instruction(Instruction::MemCpy(0xFFFF - 1, 0xFFFF - 2, 4));
+    assert_eq!(machine.dump_memory(0xFFFF - 2, 0xFFFF), b"aa");
+    assert_eq!(machine.dump_memory(0, 3), b"bcd");
+    assert!(!machine.flag);
+
+    machine.load_bytes(b"ab", 0xFFFF - 2);
+    machine.load_bytes(b"cd\0", 0);
+    machine.execute_instruction(Instruction::MemCpy(0xFFFF - 2, 0xFFFF - 1, 4));
+    assert_eq!(machine.dump_memory(0xFFFF - 2, 0xFFFF), b"bc");
+    assert_eq!(machine.dump_memory(0, 3), b"d\0\0");
+    assert!(!machine.flag);
+}
+
+#[test]
+fn memcpy_across_the_end_of_memory_can_be_stepped_back() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            memcpy 0xFFFF - 1, 100, 4;
+        },
+        0,
+    );
+    machine.load_bytes(b"abcd", 100);
+    machine.journal = Some(Journal::default());
+
+    let before = machine.clone();
+    machine.step();
+    assert_eq!(machine.dump_memory(0, 3), b"bcd");
+
+    machine.step_back().unwrap();
+    assert!(before.diff(&machine).is_empty());
+}
+
+#[test]
+fn memset_fills_memory() {
+    let mut machine = Machine::default();
+
+    machine.execute_instruction(Instruction::MemSet(100, b'x', 3));
+    assert_eq!(machine.dump_memory(99, 104), b"\0xxx\0");
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::MemSet(0xFFFF - 3, b'y', 4));
+    assert_eq!(machine.dump_memory(0xFFFF - 3, 0xFFFF), b"yyy");
+    assert_eq!(machine.dump_memory(0, 2), b"y\0");
+    assert!(!machine.flag);
+}
+
+#[test]
+fn memfilla_wraps_like_memset() {
+    let mut machine = Machine {
+        reg_a: b'z',
+        ..Machine::default()
+    };
+    machine.execute_instruction(Instruction::MemFillA(0xFFFF - 2, 4));
+
+    let mut memset = Machine::default();
+    memset.execute_instruction(Instruction::MemSet(0xFFFF - 2, b'z', 4));
+
+    assert_eq!(machine.dump_memory(0xFFFF - 2, 0xFFFF), b"zz");
+    assert_eq!(machine.dump_memory(0, 3), b"zz\0");
+    assert_eq!(machine.memory, memset.memory);
+    assert!(!machine.flag);
+}
+
+#[test]
+fn popch_rejects_invalid_characters() {
+    let mut machine = Machine::default();
+
+    for invalid in [0xD800_u32, 0x11_0000, u32::MAX] {
+        machine.reg_ch = 'x';
+        machine.flag = false;
+        machine.stack.push_bytes(&invalid.to_be_bytes()).unwrap();
+        machine.execute_instruction(Instruction::Popch);
+        assert_eq!(machine.reg_ch, 'x');
+        assert!(machine.flag);
+        assert_eq!(machine.stack.used_space(), 0);
+    }
+
+    machine.flag = false;
+    machine
+        .stack
+        .push_bytes(&0x1F600_u32.to_be_bytes())
+        .unwrap();
+    machine.execute_instruction(Instruction::Popch);
+    assert_eq!(machine.reg_ch, '😀');
+    assert!(!machine.flag);
+}
+
+#[test]
+fn ldß_rejects_invalid_utf8() {
+    let mut machine = Machine::default();
+    machine.memory[1000] = 0xFF;
+
+    machine.execute_instruction(Instruction::Ldß(1001));
+    assert_eq!(machine.reg_ß.len(), 255);
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::Ldß(1000));
+    assert!(machine.reg_ß.is_empty());
+    assert!(machine.flag);
+    assert_eq!(format!("{:?}", machine.reg_ß), "\"\"");
+}
+
+#[test]
+fn writeß_writes_a_byte_to_memory_and_reg_a() {
+    let mut machine = Machine::default();
+    machine.memory[1000..1005].copy_from_slice(b"hello");
+    machine.execute_instruction(Instruction::Ldß(1000));
+
+    machine.execute_instruction(Instruction::Writeß(2000, 1));
+    assert_eq!(machine.memory[2000], b'e');
+    assert_eq!(machine.reg_a, b'e');
+    assert!(!machine.flag);
+
+    machine.execute_instruction(Instruction::Clß);
+    machine.execute_instruction(Instruction::Writeß(2001, 0));
+    assert_eq!(machine.memory[2001], 0);
+    assert_eq!(machine.reg_a, b'e');
+    assert!(machine.flag);
+
+    machine.flag = false;
+    machine.execute_instruction(Instruction::Ldß(1000));
+    machine.execute_instruction(Instruction::Writeß(0xFFFF, 0));
+    assert_eq!(machine.reg_a, b'e');
+    assert!(machine.flag);
+}
+
+#[test]
+fn debugmemoryregion_rejects_reversed_bounds() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = machine_with_io(StreamIo::default().with_output(output.clone()));
+    machine.load_bytes(&[1, 2, 3], 100);
+
+    machine.execute_instruction(Instruction::DebugMemoryRegion(100, 103));
+    assert_eq!(*output.lock().unwrap(), b"[1, 2, 3]");
+    assert!(!machine.flag);
+
+    output.lock().unwrap().clear();
+    machine.execute_instruction(Instruction::DebugMemoryRegion(103, 100));
+    assert!(output.lock().unwrap().is_empty());
+    assert!(machine.flag);
+}
+
+#[test]
+fn num_debug_prefix_goes_to_the_debug_sink() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = machine_with_io(StreamIo::default().with_output(output.clone()));
+    machine.load_bytes(&[1, 2, 3], 100);
+    machine.num_reg = 7;
+    machine.reg_Ω.should_make_infinite_paperclips = true;
+
+    machine.execute_instruction(Instruction::DebugMemoryRegion(100, 103));
+    assert_eq!(*output.lock().unwrap(), b"7: [1, 2, 3]");
+
+    output.lock().unwrap().clear();
+    machine.debug_to_stderr = true;
+    machine.execute_instruction(Instruction::DebugMemoryRegion(100, 103));
+    assert!(output.lock().unwrap().is_empty());
+    assert!(!machine.flag);
+}
+
+#[test]
+fn debugstackregion_rejects_reversed_and_out_of_range_bounds() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = machine_with_io(StreamIo::default().with_output(output.clone()));
+    machine.stack.push_bytes(&[1, 2, 3]).unwrap();
+
+    machine.execute_instruction(Instruction::DebugStackRegion(1, 3));
+    assert_eq!(*output.lock().unwrap(), b"[2, 3]");
+    assert!(!machine.flag);
+
+    output.lock().unwrap().clear();
+    machine.execute_instruction(Instruction::DebugStackRegion(3, 1));
+    assert!(output.lock().unwrap().is_empty());
+    assert!(machine.flag);
+
+    machine.flag = false;
+    machine.execute_instruction(Instruction::DebugStackRegion(0, 1000));
+    assert!(output.lock().unwrap().is_empty());
+    assert!(machine.flag);
+}
@@ -0,0 +1,175 @@
+//! Checks that labels resolve to the right addresses and that data assembles
+//! to the right bytes in the macro and the runtime assembler.
+
+use esoteric_vm::{
+    assembly::{assemble, AssembleError},
+    esoteric_assembly, Machine,
+};
+
+/// Assembles the program with the assembly macro
+/// and also returns its source for the runtime assembler.
+macro_rules! assembly_and_source {
+    ($($t:tt)*) => {
+        (esoteric_assembly! { $($t)* }, stringify!($($t)*))
+    };
+}
+
+#[test]
+fn labels_are_addresses() {
+    let asm = esoteric_assembly! {
+        start: pushi 1;
+        data b"abc";
+        middle: align 8;
+        end: writeline middle;
+        jmemeq start, 0, end;
+    };
+
+    let mut machine = Machine::default();
+    assert_eq!(machine.load(&asm, 0), 17);
+    assert_eq!(machine.memory[9..11], [0, 5]);
+    assert_eq!(machine.memory[12..14], [0, 0]);
+    assert_eq!(machine.memory[15..17], [0, 8]);
+}
+
+#[test]
+fn forward_and_backward_jumps() {
+    // counts register L down from 3 to 0
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: pushi 0;
+            pushi 3;
+            popl;
+
+            again: pushi 0;
+            pushi done as u8;
+            pushl;
+            popb;
+            zpopep;
+            stackdealloc 2;
+            subil 1;
+            pushi 0;
+            pushi again as u8;
+            popep;
+
+            done: Ωtheendisnear;
+            Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert_eq!(machine.reg_L, 0);
+    assert!(!machine.flag);
+    assert_eq!(machine.stack.used_space(), 0);
+}
+
+#[test]
+fn labels_after_the_address_prefix() {
+    let asm = esoteric_assembly! {
+        0: first: nop;
+        1: second: writeline second;
+    };
+    let mut machine = Machine::default();
+    machine.load(&asm, 0);
+
+    assert_eq!(machine.memory[2..4], [0, 1]);
+}
+
+#[test]
+fn assembler_agrees_with_macro() {
+    let (asm, source) = assembly_and_source! {
+        0: start: pushi 0;
+        pushi end as u8;
+        popep;
+        data b"skipped";
+        middle: align 16;
+        end: writeline middle;
+        jmemeq start, 0, end;
+    };
+
+    let mut from_macro = Machine::default();
+    let mut from_text = Machine::default();
+    let len = from_macro.load(&asm, 0);
+
+    assert_eq!(from_text.load(&assemble(source).unwrap(), 0), len);
+    assert_eq!(from_macro.memory[..], from_text.memory[..]);
+}
+
+#[test]
+fn data_takes_an_array_of_bytes() {
+    let dot = b'.';
+    let mut machine = Machine::default();
+    let end = machine.load(
+        &esoteric_assembly! {
+            data [0x00, dot, 65];
+            data b"bc";
+            data [];
+        },
+        0,
+    );
+
+    assert_eq!(end, 5);
+    assert_eq!(machine.memory[..5], *b"\0.Abc");
+
+    let mut assembled = Machine::default();
+    assembled.load(
+        &assemble("data [0x00, b'.', 65]; data b\"bc\"; data []").unwrap(),
+        0,
+    );
+    assert_eq!(assembled.memory, machine.memory);
+}
+
+#[test]
+fn fill_repeats_a_byte() {
+    let mut machine = Machine::default();
+    machine.memory[..8].fill(0xFF);
+    let end = machine.load(
+        &esoteric_assembly! {
+            fill b'x', 3;
+            FILL 0, 2;
+            byte 1;
+        },
+        0,
+    );
+
+    assert_eq!(end, 6);
+    assert_eq!(machine.memory[..8], [b'x', b'x', b'x', 0, 0, 1, 0xFF, 0xFF]);
+
+    let mut assembled = Machine::default();
+    assembled.memory[..8].fill(0xFF);
+    assembled.load(&assemble("fill b'x', 3; FILL 0, 2; byte 1").unwrap(), 0);
+    assert_eq!(assembled.memory, machine.memory);
+}
+
+#[test]
+fn assembler_rejects_bad_labels() {
+    assert_eq!(
+        assemble("nop;\nwriteline nowhere;").err(),
+        Some(AssembleError {
+            line: 2,
+            message: "the label `nowhere` isn't defined".to_owned()
+        })
+    );
+    assert_eq!(
+        assemble("twice: nop;\ntwice: nop;").err(),
+        Some(AssembleError {
+            line: 2,
+            message: "the label `twice` is defined twice".to_owned()
+        })
+    );
+    assert_eq!(
+        assemble("fill 0, end;\nx: nop;\nend: nop;").err(),
+        Some(AssembleError {
+            line: 1,
+            message: "the amount of `fill` can't be a label".to_owned()
+        })
+    );
+    assert_eq!(
+        assemble("nop;\nalign end;\nend: nop;").err(),
+        Some(AssembleError {
+            line: 2,
+            message: "the alignment of `align` can't be a label".to_owned()
+        })
+    );
+}
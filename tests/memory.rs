@@ -0,0 +1,89 @@
+//! Checks the memory accessors.
+
+use esoteric_vm::{
+    esoteric_assembly, instruction::InstructionKind, machine::LoadError, Machine, VmError,
+};
+
+#[test]
+fn memory_slice_reads_back_loaded_bytes() {
+    let mut machine = Machine::default();
+    machine.load_bytes(b"hello", 1000);
+
+    assert_eq!(machine.memory_slice(1000, 5), b"hello");
+    assert_eq!(machine.memory_slice(1001, 3), b"ell");
+    assert_eq!(machine.memory_slice(1000, 0), b"");
+}
+
+#[test]
+fn memory_slice_clamps_to_the_end_of_memory() {
+    let mut machine = Machine::default();
+    machine.load_bytes(b"xyz", 0xFFFC);
+
+    assert_eq!(machine.memory_slice(0xFFFC, u16::MAX), b"xyz");
+    assert_eq!(machine.memory_slice(u16::MAX, u16::MAX), b"");
+}
+
+#[test]
+fn memory_mut_patches_memory() {
+    let mut machine = Machine::default();
+    machine.load_bytes(b"hello", 1000);
+
+    machine.memory_mut(1000, 1).copy_from_slice(b"j");
+    assert_eq!(machine.memory_slice(1000, 5), b"jello");
+
+    let tail = machine.memory_mut(0xFFFD, 10);
+    assert_eq!(tail.len(), 2);
+    tail.fill(7);
+    assert_eq!(machine.memory[0xFFFD..], [7, 7]);
+}
+
+#[test]
+fn try_load_fills_memory_up_to_the_end() {
+    let mut machine = Machine::default();
+    let program = esoteric_assembly! {
+        data b"abc";
+        pushi 7;
+    };
+
+    assert_eq!(machine.try_load(&program, 0xFFFA), Ok(0xFFFF));
+    assert_eq!(machine.memory_slice(0xFFFA, 3), b"abc");
+    assert_eq!(
+        machine.memory_slice(0xFFFD, 2),
+        [InstructionKind::Pushi as u8, 7]
+    );
+}
+
+#[test]
+fn try_load_rejects_overflowing_by_one_byte() {
+    let mut machine = Machine::default();
+    let program = esoteric_assembly! {
+        data b"abc";
+        pushi 7;
+    };
+
+    assert_eq!(
+        machine.try_load(&program, 0xFFFB),
+        Err(LoadError::Overflow { at: 0xFFFE })
+    );
+    assert!(machine.memory.iter().all(|&byte| byte == 0));
+    assert_eq!(
+        VmError::from(LoadError::Overflow { at: 0xFFFE }).to_string(),
+        "data loaded at address 65534 goes past the end of memory"
+    );
+}
+
+#[test]
+fn load_still_skips_data_that_does_not_fit() {
+    let mut machine = Machine::default();
+    let program = esoteric_assembly! {
+        data b"abc";
+        byte 1;
+    };
+
+    assert_eq!(
+        machine.try_load(&program, 0xFFFD),
+        Err(LoadError::Overflow { at: 0xFFFD })
+    );
+    assert_eq!(machine.load(&program, 0xFFFD), 0xFFFE);
+    assert_eq!(machine.memory_slice(0xFFFD, 2), [1, 0]);
+}
@@ -0,0 +1,30 @@
+//! Checks the lists of fibonacci primes and semiprimes.
+
+use esoteric_vm::primes::{
+    is_fib_prime_or_semiprime_u16, is_fib_prime_or_semiprime_u32, FIB_PRIME_AND_SEMIPRIME_LIST_U32,
+};
+
+#[test]
+fn u16_and_u32_agree() {
+    for n in 0..=u16::MAX {
+        assert_eq!(
+            is_fib_prime_or_semiprime_u16(n),
+            is_fib_prime_or_semiprime_u32(n.into()),
+            "{n}"
+        );
+    }
+}
+
+#[test]
+fn the_list_matches_the_check() {
+    assert!(FIB_PRIME_AND_SEMIPRIME_LIST_U32.is_sorted());
+    assert!(FIB_PRIME_AND_SEMIPRIME_LIST_U32
+        .iter()
+        .all(|&n| is_fib_prime_or_semiprime_u32(n)));
+    assert_eq!(
+        (0..=u32::from(u16::MAX))
+            .filter(|&n| is_fib_prime_or_semiprime_u32(n))
+            .count(),
+        15
+    );
+}
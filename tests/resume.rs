@@ -0,0 +1,173 @@
+//! Checks that a machine stopped by an instruction limit
+//! resumes exactly where it left off.
+
+use std::sync::{Arc, Mutex};
+
+use esoteric_vm::{esoteric_assembly, machine::RunOutcome, Machine};
+
+/// The machine of the `99_bottles_of_beer` example, writing to `output`.
+fn bottles(output: Arc<Mutex<Vec<u8>>>) -> Machine {
+    let mut machine = Machine::default().with_output(output);
+
+    let asm = esoteric_assembly! {
+        // initialize dot pointer so that IO operations work
+
+        // push a dot character to stack
+        0: pushi b'.';
+        // pop to address 28657
+        2: pop 28657;
+
+        // set dot pointer to 28657 (has to be a prime or semiprime, which is also a fibonacci number)
+        5: ldidp 28657;
+
+        // -----------------
+
+
+        // set L to 99
+        8: pushi 0;
+        10: pushi 99;
+        12: popl;
+
+        // set num to 99
+        13: pushi 0;
+        15: pushi 0;
+        17: pushi 0;
+        19: pushi 99;
+        21: popnum;
+
+        // set B to 1
+        22: pushi 0;
+        24: pushi 1;
+        26: popb;
+
+        27: Ωsetpaperclipproduction true;
+
+        // WORK
+            29: writeline 110; // wall
+            33: writeline 140; // bottles
+
+            35: Ωsetpaperclipproduction false;
+
+            37: writeline 158; // take
+
+            40: Ωsetpaperclipproduction true;
+
+            // jump to fin if L == 1
+            42: cmplb;
+            43: pushi 0;
+            45: pushi 71;
+
+            47: zpopep;
+
+            48: stackdealloc 2;
+
+            // set B to 1
+            51: pushi 0;
+            53: pushi 1;
+            55: popb;
+
+            56: subbl; // L -= 1
+
+            // set num to L
+            57: pushi 0;
+            59: pushi 0;
+            61: pushl;
+            62: popnum;
+
+            63: writeline 193; // wall_end
+
+            // jump back to work
+            66: pushi 0;
+            68: pushi 29;
+
+            70: popep;
+
+        // FIN
+            71: Ωsetpaperclipproduction false;
+
+            73: writeline 224; // no_more
+            76: writeline 193; // wall_end
+
+            79: writeline 233; // No_more
+            82: writeline 110; // wall
+            85: writeline 224; // no_more
+            88: writeline 140; // bottles
+
+            91: writeline 242; // store
+
+            // set num to 99
+            94: pushi 0;
+            96: pushi 0;
+            98: pushi 0;
+            100: pushi 99;
+            102: popnum;
+
+            103: Ωsetpaperclipproduction true;
+
+            105: writeline 193; // wall_end
+
+        // halt machine
+        108: Ωtheendisnear;
+        109: Ωskiptothechase;
+
+        // wall (30 bytes)
+        110: data b"bottles of beer on the wall, \0";
+        // bottles (18 bytes)
+        140: data b"bottles of beer.\n\0";
+        // take (35 bytes)
+        158: data b"Take one down and pass it around, \0";
+        // wall end (31 bytes)
+        193: data b"bottles of beer on the wall.\n\n\0";
+
+        // no more (9 bytes)
+        224: data b"no more \0";
+        // No more (9 bytes)
+        233: data b"No more \0";
+
+        // store
+        242: data b"Go to the store and buy some more, \0";
+    };
+
+    machine.load(&asm, 0);
+    machine
+}
+
+#[test]
+fn running_in_single_instruction_quanta_is_transparent() {
+    let uncapped_output = Arc::new(Mutex::new(Vec::new()));
+    let mut uncapped = bottles(uncapped_output.clone());
+    let exit_code = uncapped.run();
+    assert!(uncapped_output
+        .lock()
+        .unwrap()
+        .starts_with(b"99: bottles of beer on the wall, "));
+
+    let capped_output = Arc::new(Mutex::new(Vec::new()));
+    let mut capped = bottles(capped_output.clone());
+    while capped.run_for(1) == RunOutcome::LimitReached {}
+
+    assert_eq!(capped.run_for(1), RunOutcome::Halted(exit_code));
+    assert_eq!(
+        *capped_output.lock().unwrap(),
+        *uncapped_output.lock().unwrap()
+    );
+    assert_eq!(capped.state_checksum(), uncapped.state_checksum());
+}
+
+#[test]
+fn run_continues_after_the_limit() {
+    let uncapped_output = Arc::new(Mutex::new(Vec::new()));
+    let mut uncapped = bottles(uncapped_output.clone());
+    uncapped.run();
+
+    let resumed_output = Arc::new(Mutex::new(Vec::new()));
+    let mut resumed = bottles(resumed_output.clone());
+    assert_eq!(resumed.run_for(1000), RunOutcome::LimitReached);
+    resumed.run();
+
+    assert_eq!(
+        *resumed_output.lock().unwrap(),
+        *uncapped_output.lock().unwrap()
+    );
+    assert_eq!(resumed.state_checksum(), uncapped.state_checksum());
+}
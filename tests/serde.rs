@@ -0,0 +1,88 @@
+//! Checks that a serialized machine continues exactly like the original.
+#![cfg(feature = "serde")]
+
+use esoteric_vm::{
+    esoteric_assembly,
+    machine::{omega::Ω, stack::Stack},
+    ConstantSizeString, Machine,
+};
+
+/// A machine running a loop that shuffles memory, pushes to the stack
+/// and counts in register L.
+fn machine() -> Machine {
+    let mut machine = Machine::default().with_stack_capacity(1000);
+    machine.load(
+        &esoteric_assembly! {
+            pushi 0;
+            pushi 1;
+            popb;
+            again: shuffle 100, 16;
+            pushl;
+            addbl;
+            pushi 0;
+            pushi again as u8;
+            popep;
+        },
+        0,
+    );
+    machine.memory[100..116].copy_from_slice(b"esoteric machine");
+    machine.rng_state = 42;
+    machine.reg_ř[36] = -1;
+    machine.reg_ß = ConstantSizeString::try_new(b"\xC3\x9F".to_vec()).unwrap();
+    machine.reg_Ω.illusion_of_choice = Some(None);
+    machine
+}
+
+#[test]
+fn deserialized_machine_continues_the_same() {
+    let mut machine = machine();
+    for _ in 0..100 {
+        machine.step();
+    }
+
+    let json = serde_json::to_string(&machine).unwrap();
+    let mut restored: Machine = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.state_checksum(), machine.state_checksum());
+    assert_eq!(restored.stack.total_space(), 1000);
+
+    for _ in 0..100 {
+        machine.step();
+        restored.step();
+    }
+
+    assert_eq!(restored.state_checksum(), machine.state_checksum());
+    assert_eq!(restored.reg_L, machine.reg_L);
+    assert_eq!(restored.reg_Ω, machine.reg_Ω);
+    assert_eq!(restored.memory[100..116], machine.memory[100..116]);
+}
+
+#[test]
+fn invalid_states_are_rejected() {
+    let json = serde_json::to_string(&machine()).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    value["stack"]["capacity"] = 0.into();
+    value["stack"]["contents"] = vec![1, 2].into();
+    assert!(serde_json::from_value::<Machine>(value.clone()).is_err());
+
+    value["stack"]["contents"] = Vec::<u8>::new().into();
+    value["memory"] = vec![0; 10].into();
+    assert!(serde_json::from_value::<Machine>(value.clone()).is_err());
+
+    value["memory"] = vec![0; 0xFFFF].into();
+    assert!(serde_json::from_value::<Machine>(value.clone()).is_ok());
+
+    let huge = r#"{"capacity":18446744073709551615,"contents":[]}"#;
+    assert!(serde_json::from_str::<Stack>(huge).is_err());
+    assert!(serde_json::from_str::<ConstantSizeString>(&huge.replace("[]", "\"\"")).is_err());
+    value["stack"]["capacity"] = u64::MAX.into();
+    assert!(serde_json::from_value::<Machine>(value.clone()).is_err());
+
+    value["stack"]["capacity"] = 16.into();
+    value["reg_ß"]["capacity"] = u64::MAX.into();
+    assert!(serde_json::from_value::<Machine>(value).is_err());
+
+    let mut omega = serde_json::to_value(Ω::ZEROED).unwrap();
+    omega["illusion_of_choice"] = 200.into();
+    assert!(serde_json::from_value::<Ω>(omega).is_err());
+}
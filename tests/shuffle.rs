@@ -0,0 +1,76 @@
+//! Checks that `shuffle` is a deterministic permutation for a given seed.
+
+use esoteric_vm::{esoteric_assembly, machine::journal::Journal, Machine};
+
+/// Shuffles the bytes `0..64` at address 1000 with the generator seeded with `seed`.
+fn shuffled(seed: u64) -> Vec<u8> {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: shuffle 1000, 64;
+            5: Ωtheendisnear;
+            6: Ωskiptothechase;
+        },
+        0,
+    );
+    for (byte, value) in machine.memory[1000..1064].iter_mut().zip(0..) {
+        *byte = value;
+    }
+    machine.rng_state = seed;
+    machine.run();
+
+    assert!(!machine.flag);
+    machine.memory[1000..1064].to_vec()
+}
+
+#[test]
+fn same_seed_same_order() {
+    assert_eq!(shuffled(42), shuffled(42));
+    assert_ne!(shuffled(42), shuffled(43));
+}
+
+#[test]
+fn shuffling_keeps_the_bytes() {
+    let mut bytes = shuffled(7);
+    assert_ne!(bytes, (0..64).collect::<Vec<u8>>());
+
+    bytes.sort_unstable();
+    assert_eq!(bytes, (0..64).collect::<Vec<u8>>());
+}
+
+#[test]
+fn shuffling_past_the_end_of_memory_sets_the_flag() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: shuffle 0xFFF0, 0x20;
+            5: Ωtheendisnear;
+            6: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert!(machine.flag);
+}
+
+#[test]
+fn stepping_back_restores_the_generator() {
+    let mut machine = Machine::default();
+    machine.load(&esoteric_assembly! { shuffle 1000, 64; }, 0);
+    for (byte, value) in machine.memory[1000..1064].iter_mut().zip(0..) {
+        *byte = value;
+    }
+    machine.rng_state = 42;
+    machine.journal = Some(Journal::default());
+
+    machine.step();
+    let first = machine.memory[1000..1064].to_vec();
+
+    machine.step_back().unwrap();
+    assert_eq!(machine.rng_state, 42);
+    assert_eq!(machine.memory[1000..1064], (0..64).collect::<Vec<u8>>());
+
+    machine.step();
+    assert_eq!(machine.memory[1000..1064], first);
+}
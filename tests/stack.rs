@@ -0,0 +1,118 @@
+//! Checks the stack's capacity, peeking and typed values.
+
+use esoteric_vm::{
+    esoteric_assembly,
+    machine::{journal::Journal, stack::Stack},
+    Machine,
+};
+
+#[test]
+fn custom_capacity_overflows_by_one_byte() {
+    let mut machine = Machine::default().with_stack_capacity(8192);
+    machine.load(
+        &esoteric_assembly! {
+            0: stackalloc 8192;
+            3: Ωtheendisnear;
+            4: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert!(!machine.flag);
+    assert_eq!(machine.stack.used_space(), 8192);
+    assert_eq!(machine.stack.space_left(), 0);
+
+    assert!(machine.stack.push_byte(0).is_err());
+    assert_eq!(machine.stack.used_space(), 8192);
+}
+
+#[test]
+fn default_capacity_is_smaller() {
+    let mut machine = Machine::default();
+    machine.load(
+        &esoteric_assembly! {
+            0: stackalloc 8192;
+            3: Ωtheendisnear;
+            4: Ωskiptothechase;
+        },
+        0,
+    );
+    machine.run();
+
+    assert!(machine.flag);
+    assert_eq!(machine.stack.used_space(), 0);
+}
+
+#[test]
+fn peek_then_pop_yields_the_same_value() {
+    let mut machine = Machine::default();
+    let stack = &mut machine.stack;
+    stack.push_bytes(&[0x12, 0x34, 0x56, 0x78, 0x9A]).unwrap();
+
+    assert_eq!(stack.peek_u32(), Some(0x3456_789A));
+    assert_eq!(stack.peek_u32(), stack.clone().pop_u32());
+    assert_eq!(stack.used_space(), 5);
+
+    assert_eq!(stack.peek_byte(), Some(0x9A));
+    assert_eq!(stack.peek_byte(), stack.pop_byte());
+    assert_eq!(stack.peek_u16(), Some(0x5678));
+    assert_eq!(stack.peek_u16(), stack.pop_u16());
+    assert_eq!(stack.peek_u32(), None);
+    assert_eq!(stack.peek_u16(), stack.pop_u16());
+    assert_eq!(stack.peek_byte(), stack.pop_byte());
+
+    assert_eq!(stack.peek_byte(), None);
+    assert_eq!(stack.peek_u16(), None);
+}
+
+#[test]
+fn signed_and_float_values_round_trip() {
+    let mut stack = Stack::default();
+
+    stack.push_i16(i16::MIN).unwrap();
+    stack.push_i32(-2).unwrap();
+    stack.push_f64(-1.5).unwrap();
+    assert_eq!(stack.used_space(), 14);
+    assert_eq!(stack.vec[2..6], [0xFF, 0xFF, 0xFF, 0xFE]);
+
+    assert_eq!(stack.pop_f64(), Some(-1.5));
+    assert_eq!(stack.pop_i32(), Some(-2));
+    assert_eq!(stack.pop_i16(), Some(i16::MIN));
+    assert_eq!(stack.pop_i16(), None);
+
+    let mut stack = Stack::with_capacity(7);
+    assert!(stack.push_f64(0.0).is_err());
+    assert_eq!(stack.used_space(), 0);
+}
+
+#[test]
+fn stackclear_resets_scratch_space() {
+    let mut machine = Machine::default().with_stack_capacity(4);
+    machine.load(
+        &esoteric_assembly! {
+            pushi 1;
+            pushi 2;
+            pushi 3;
+            stackclear;
+            jmp -10;
+        },
+        0,
+    );
+    machine.journal = Some(Journal::default());
+
+    for _ in 0..52 {
+        machine.step();
+    }
+    assert!(!machine.flag);
+    assert_eq!(machine.stack.used_space(), 2);
+
+    let before = machine.clone();
+    machine.step();
+    machine.step();
+    assert_eq!(machine.stack.used_space(), 0);
+
+    machine.step_back().unwrap();
+    machine.step_back().unwrap();
+    assert!(before.diff(&machine).is_empty());
+}
@@ -0,0 +1,85 @@
+//! Checks [`Machine::run_traced`].
+
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+};
+
+use esoteric_vm::{esoteric_assembly, instruction::Instruction, machine::io::StreamIo, Machine};
+
+#[test]
+fn truth_machine_trace() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut machine = Machine::default().with_io(
+        StreamIo::default()
+            .with_input(Arc::new(Mutex::new(Cursor::new(b"0"))))
+            .with_output(output.clone()),
+    );
+    machine.load(
+        &esoteric_assembly! {
+            0: pushi b'.';
+            2: pop 28657;
+            5: ldidp 28657;
+            8: getchar;
+            9: pushch;
+            10: popb;
+            11: stackdealloc 2;
+            14: pushi 0;
+            16: pushi 49;
+            18: popl;
+            19: cmplb;
+            20: pushi 0;
+            22: pushi 30;
+            24: zpopep;
+            25: stackdealloc 2;
+            28: writechar;
+            29: Ωtheendisnear;
+            30: Ωskiptothechase;
+        },
+        0,
+    );
+
+    let mut trace = Vec::new();
+    machine.run_traced(|address, instruction, machine| {
+        trace.push((address, *instruction, machine.reg_ep));
+    });
+
+    assert_eq!(
+        trace,
+        [
+            (0, Instruction::Pushi(b'.'), 2),
+            (2, Instruction::Pop(28657), 5),
+            (5, Instruction::Ldidp(28657), 8),
+            (8, Instruction::GetChar, 9),
+            (9, Instruction::Pushch, 10),
+            (10, Instruction::Popb, 11),
+            (11, Instruction::StackDealloc(2), 14),
+            (14, Instruction::Pushi(0), 16),
+            (16, Instruction::Pushi(49), 18),
+            (18, Instruction::PopL, 19),
+            (19, Instruction::CmpLB, 20),
+            (20, Instruction::Pushi(0), 22),
+            (22, Instruction::Pushi(30), 24),
+            (24, Instruction::Zpopep, 25),
+            (25, Instruction::StackDealloc(2), 28),
+            (28, Instruction::WriteChar, 29),
+            (29, Instruction::ΩTheEndIsNear, 30),
+            (30, Instruction::ΩSkipToTheChase, 31),
+        ]
+    );
+    assert!(machine.halted);
+    assert_eq!(*output.lock().unwrap(), b"0");
+}
+
+#[test]
+fn halted_machine_traces_nothing() {
+    let mut machine = Machine::default();
+    machine.load(&esoteric_assembly! { halt; }, 0);
+    machine.halted = true;
+
+    let mut calls = 0;
+    machine.run_traced(|_, _, _| calls += 1);
+
+    assert_eq!(calls, 0);
+    assert_eq!(machine.reg_ep, 0);
+}
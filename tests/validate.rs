@@ -0,0 +1,32 @@
+//! Checks the warnings of `validate`.
+
+use esoteric_vm::{
+    assembly::{validate, Warning},
+    esoteric_assembly,
+};
+
+#[test]
+fn every_io_instruction_needs_a_dot_pointer() {
+    let programs = [
+        esoteric_assembly! { getbyte; halt; },
+        esoteric_assembly! { ateof; halt; },
+        esoteric_assembly! { putbyte; halt; },
+        esoteric_assembly! { writelineifflag 0; halt; },
+        esoteric_assembly! { writestack 0, 1; halt; },
+        esoteric_assembly! { readnum; halt; },
+        esoteric_assembly! { writenum; halt; },
+    ];
+
+    for program in programs {
+        assert_eq!(validate(&program), [Warning::MissingDotPointer]);
+    }
+}
+
+#[test]
+fn getchar_and_a_dot_pointer_dont_warn() {
+    assert_eq!(validate(&esoteric_assembly! { getchar; halt; }), []);
+    assert_eq!(
+        validate(&esoteric_assembly! { ldidp 28657; writenum; halt; }),
+        []
+    );
+}